@@ -0,0 +1,196 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use cgmath::*;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use teapot::{BasisTable, BezierPatch, Mesh};
+
+const N: usize = 256;
+
+// The original tesselate_patch, kept here to measure against: the basis
+// polynomials are worked out afresh at every vertex of every patch, and
+// multiplied out into three 4 X 4 weight matrices, one for the position
+// and one for each partial. It makes no tangents.
+fn baseline_tesselate_patch(patch: &BezierPatch, nr: usize, nc: usize) -> Mesh {
+    let cpts = patch.points();
+    let mut verts: Vec<[f32; 4]> = Vec::with_capacity(nr * nc);
+    let mut norms: Vec<[f32; 3]> = Vec::with_capacity(nr * nc);
+    let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(nr * nc);
+
+    for r in 0..nr {
+        let v = r as f64 / (nr - 1) as f64;
+        let v2 = v * v;
+        let v3 = v * v2;
+        let mv = 1.0 - v;
+        let mv2 = mv * mv;
+        let mv3 = mv * mv2;
+
+        let vp = Vector4::new(mv3, 3.0 * mv2 * v, 3.0 * mv * v2, v3);
+        let dv = Vector4::new(
+            -3.0 + 6.0 * v - 3.0 * v2,
+            3.0 * (1.0 - 4.0 * v + 3.0 * v2),
+            3.0 * (2.0 * v - 3.0 * v2),
+            3.0 * v2,
+        );
+
+        for c in 0..nc {
+            let u = c as f64 / (nc - 1) as f64;
+            let u2 = u * u;
+            let u3 = u * u2;
+            let mu = 1.0 - u;
+            let mu2 = mu * mu;
+            let mu3 = mu * mu2;
+
+            let up = Vector4::new(mu3, 3.0 * mu2 * u, 3.0 * mu * u2, u3);
+            let du = Vector4::new(
+                -3.0 + 6.0 * u - 3.0 * u2,
+                3.0 * (1.0 - 4.0 * u + 3.0 * u2),
+                3.0 * (2.0 * u - 3.0 * u2),
+                3.0 * u2,
+            );
+
+            let outer = |a: Vector4<f64>, b: Vector4<f64>| {
+                Matrix4::new(
+                    a[0] * b[0],
+                    a[1] * b[0],
+                    a[2] * b[0],
+                    a[3] * b[0],
+                    a[0] * b[1],
+                    a[1] * b[1],
+                    a[2] * b[1],
+                    a[3] * b[1],
+                    a[0] * b[2],
+                    a[1] * b[2],
+                    a[2] * b[2],
+                    a[3] * b[2],
+                    a[0] * b[3],
+                    a[1] * b[3],
+                    a[2] * b[3],
+                    a[3] * b[3],
+                )
+            };
+            let w = outer(up, vp);
+            let dwdv = outer(du, vp);
+            let dwdu = outer(up, dv);
+
+            let mut pt = Vector3::new(0f64, 0f64, 0f64);
+            let mut tan1 = Vector3::new(0f64, 0f64, 0f64);
+            let mut tan2 = Vector3::new(0f64, 0f64, 0f64);
+            for (a, row) in cpts.iter().enumerate() {
+                for (b, p) in row.iter().enumerate() {
+                    let cpt = p.to_vec().cast::<f64>().unwrap();
+                    pt += w[a][b] * cpt;
+                    tan1 += dwdv[a][b] * cpt;
+                    tan2 += dwdu[a][b] * cpt;
+                }
+            }
+
+            verts.push([pt[0] as f32, pt[1] as f32, pt[2] as f32, 1.0]);
+            let normal = tan1.normalize().cross(tan2.normalize());
+            norms.push([normal[0] as f32, normal[1] as f32, normal[2] as f32]);
+            uvs.push([u as f32, v as f32]);
+        }
+    }
+
+    let mut indices: Vec<u32> = Vec::with_capacity(2 * 3 * (nr - 1) * (nc - 1));
+    for r in 0..(nr - 1) {
+        for c in 0..(nc - 1) {
+            indices.push((r * nc + c) as u32);
+            indices.push((r * nc + c + 1) as u32);
+            indices.push(((r + 1) * nc + c + 1) as u32);
+            indices.push((r * nc + c) as u32);
+            indices.push(((r + 1) * nc + c + 1) as u32);
+            indices.push(((r + 1) * nc + c) as u32);
+        }
+    }
+
+    let mut mesh = Mesh::new();
+    mesh.vertices = verts;
+    mesh.normals = norms;
+    mesh.uvs = uvs;
+    mesh.indices = indices;
+    mesh
+}
+
+// The original create_vertices, with the baseline for each patch
+fn evaluate_each_vertex(patches: &teapot::PatchSet, n: usize) -> Mesh {
+    let mut mesh = Mesh::new();
+    for patch in patches.iter() {
+        mesh.append(&baseline_tesselate_patch(patch, n, n));
+    }
+    mesh
+}
+
+fn tessellate(c: &mut Criterion) {
+    let patches = teapot::control_points();
+    let mut group = c.benchmark_group("256x256");
+    group.sample_size(10);
+
+    // just the surface and its partials
+    group.bench_function("evaluate positions and partials", |b| {
+        b.iter(|| {
+            let mut sum = 0.0;
+            for patch in patches.iter() {
+                for r in 0..N {
+                    for c in 0..N {
+                        let p =
+                            patch.evaluate(c as f64 / (N - 1) as f64, r as f64 / (N - 1) as f64);
+                        sum += p.position.x + p.du.y + p.dv.z;
+                    }
+                }
+            }
+            sum
+        })
+    });
+    group.bench_function("basis table positions and partials", |b| {
+        let basis = BasisTable::new(N);
+        b.iter(|| {
+            let mut sum = 0.0;
+            for patch in patches.iter() {
+                patch.for_each_grid_sample(&basis, &basis, |_, _, s| {
+                    sum += s.position.x + s.du.y + s.dv.z;
+                });
+            }
+            sum
+        })
+    });
+
+    // whole meshes, with normals, and tangents from all but the baseline
+    group.bench_function("evaluate each vertex", |b| {
+        b.iter(|| evaluate_each_vertex(black_box(&patches), N))
+    });
+    group.bench_function("tesselate_patch", |b| {
+        b.iter(|| {
+            let mut mesh = Mesh::new();
+            for patch in patches.iter() {
+                mesh.append(&teapot::tesselate_patch(black_box(patch), N, N));
+            }
+            mesh
+        })
+    });
+    group.bench_function("tesselate_patch_with shared tables", |b| {
+        let basis = BasisTable::new(N);
+        b.iter(|| {
+            let mut mesh = Mesh::new();
+            for patch in patches.iter() {
+                mesh.append(&teapot::tesselate_patch_with(
+                    black_box(patch),
+                    &basis,
+                    &basis,
+                ));
+            }
+            mesh
+        })
+    });
+    group.bench_function("create_vertices", |b| {
+        b.iter(|| teapot::create_vertices(black_box(N), N))
+    });
+    // the same on one thread, to compare with the parallel feature
+    let resolutions = vec![(N, N); patches.len()];
+    group.bench_function("tesselate_stitched_serial", |b| {
+        b.iter(|| teapot::tesselate_stitched_serial(&patches, black_box(&resolutions)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, tessellate);
+criterion_main!(benches);
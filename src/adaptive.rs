@@ -0,0 +1,148 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use crate::mesh::Mesh;
+use crate::patch::{BezierPatch, PatchSet};
+use crate::stitch::{stitched_triangle_count, tesselate_stitched};
+use cgmath::*;
+
+/// Most rows or columns of vertices `patch_resolution` will ask for.
+pub const MAX_RESOLUTION: usize = 1025;
+
+/// The number of rows and columns of vertices needed to keep the triangles
+/// of a [`tesselate_patch`](crate::tesselate_patch) grid within `tolerance` of the surface.
+///
+/// This uses the usual bound on the distance between a surface and the
+/// bilinear interpolant of its samples on an `m` X `n` grid of spans,
+///
+/// `(Muu / m² + 2 Muv / (m n) + Mvv / n²) / 8`,
+///
+/// where the `M`s bound the second partials. For a cubic patch these are
+/// at most 6 times the largest second difference of the control points
+/// along `u` or `v`, and 9 times the largest mixed difference.
+pub fn patch_resolution(patch: &BezierPatch, tolerance: f32) -> (usize, usize) {
+    let (mr, mc) = second_partial_bounds(patch);
+
+    // 2 / (m n) <= 1 / m² + 1 / n², so splitting the tolerance evenly
+    // between the two directions is enough
+    let spans = |m: f32| {
+        if m <= 0.0 {
+            1
+        } else {
+            let n = (m / (4.0 * tolerance)).sqrt().ceil();
+            if n.is_finite() {
+                (n as usize).max(1)
+            } else {
+                MAX_RESOLUTION - 1
+            }
+        }
+    };
+    let nr = (spans(mr) + 1).min(MAX_RESOLUTION);
+    let nc = (spans(mc) + 1).min(MAX_RESOLUTION);
+    (nr, nc)
+}
+
+// The bounds on the second partials that set the number of rows and of
+// columns, Mvv + Muv and Muu + Muv
+fn second_partial_bounds(patch: &BezierPatch) -> (f32, f32) {
+    let p = patch.points();
+    let second = |a: Point3<f32>, b: Point3<f32>, c: Point3<f32>| ((c - b) - (b - a)).magnitude();
+
+    let mut muu = 0f32;
+    let mut mvv = 0f32;
+    let mut muv = 0f32;
+    for row in p.iter() {
+        for w in row.windows(3) {
+            muu = muu.max(second(w[0], w[1], w[2]));
+        }
+    }
+    for rows in p.windows(3) {
+        for ((&a, &b), &c) in rows[0].iter().zip(&rows[1]).zip(&rows[2]) {
+            mvv = mvv.max(second(a, b, c));
+        }
+    }
+    for a in 0..3 {
+        for b in 0..3 {
+            let twist = (p[a + 1][b + 1] - p[a + 1][b]) - (p[a][b + 1] - p[a][b]);
+            muv = muv.max(twist.magnitude());
+        }
+    }
+    let muu = 6.0 * muu;
+    let mvv = 6.0 * mvv;
+    let muv = 9.0 * muv;
+    (mvv + muv, muu + muv)
+}
+
+/// Tessellates each patch in `patches` at the resolution
+/// [`patch_resolution`] picks for it, so that no triangle is further than
+/// `tolerance` from the surface. Small, flat patches get few triangles and
+/// big, curved ones get many. Neighbouring patches are stitched together
+/// where their resolutions differ, as in [`tesselate_stitched`].
+pub fn tesselate_adaptive(patches: &PatchSet, tolerance: f32) -> Mesh {
+    let resolutions: Vec<(usize, usize)> = patches
+        .iter()
+        .map(|p| patch_resolution(p, tolerance))
+        .collect();
+    tesselate_stitched(patches, &resolutions)
+}
+
+/// Number of triangles [`tesselate_adaptive`] makes for `tolerance`,
+/// including the strips stitching patches together, without evaluating
+/// the surface.
+pub fn adaptive_triangle_count(patches: &PatchSet, tolerance: f32) -> usize {
+    let resolutions: Vec<(usize, usize)> = patches
+        .iter()
+        .map(|p| patch_resolution(p, tolerance))
+        .collect();
+    stitched_triangle_count(patches, &resolutions)
+}
+
+/// The smallest tolerance for which [`tesselate_adaptive`] makes no more
+/// than `max_triangles` triangles, to within a fraction of a percent. Use
+/// this to spend a triangle budget evenly across the model.
+///
+/// Not every budget can be met. If even one quad per patch is too many,
+/// this returns the tolerance that gives that, the coarsest tessellation
+/// there is, and the mesh is over budget. If every curved patch at
+/// [`MAX_RESOLUTION`] still fits, it returns the largest tolerance that
+/// gives that, as smaller ones add no more triangles.
+pub fn tolerance_for_budget(patches: &PatchSet, max_triangles: usize) -> f32 {
+    let bounds: Vec<f32> = patches
+        .iter()
+        .flat_map(|p| {
+            let (mr, mc) = second_partial_bounds(p);
+            vec![mr, mc]
+        })
+        .filter(|m| *m > 0.0 && m.is_finite())
+        .collect();
+    if bounds.is_empty() {
+        // flat patches get one quad whatever the tolerance
+        return f32::MIN_POSITIVE;
+    }
+
+    // spans(m) in patch_resolution is 1 from m / 4 up, and reaches
+    // MAX_RESOLUTION - 1 at m / (4 (MAX_RESOLUTION - 1)²), for the rows and
+    // columns of every patch
+    let finest = 4.0 * ((MAX_RESOLUTION - 1) * (MAX_RESOLUTION - 1)) as f32;
+    let mut hi = bounds.iter().fold(0f32, |a, &m| a.max(m / 4.0));
+    let mut lo = bounds
+        .iter()
+        .fold(f32::MAX, |a, &m| a.min(m / finest))
+        .max(f32::MIN_POSITIVE);
+    if adaptive_triangle_count(patches, hi) > max_triangles {
+        return hi;
+    }
+    if adaptive_triangle_count(patches, lo) <= max_triangles {
+        return lo;
+    }
+
+    // bisect on a log scale
+    for _ in 0..32 {
+        let mid = (lo * hi).sqrt();
+        if adaptive_triangle_count(patches, mid) <= max_triangles {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    hi
+}
@@ -0,0 +1,131 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use crate::patch::BezierPatch;
+use crate::surface::bernstein;
+use cgmath::*;
+
+/// The cubic Bernstein polynomials and their derivatives at `n` evenly
+/// spaced parameters from 0 to 1, the rows or columns of a tessellation
+/// grid.
+///
+/// These only depend on the grid size, so one table serves every patch
+/// tessellated at that size.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BasisTable {
+    values: Vec<Vector4<f64>>,
+    derivatives: Vec<Vector4<f64>>,
+}
+
+impl BasisTable {
+    /// Samples the basis at `n` parameters, `i / (n - 1)` for `i` in
+    /// `0..n`. `n` must be at least 2.
+    pub fn new(n: usize) -> BasisTable {
+        assert!(n >= 2, "a basis table needs at least 2 samples");
+        let (values, derivatives) = (0..n).map(|i| bernstein(i as f64 / (n - 1) as f64)).unzip();
+        BasisTable {
+            values,
+            derivatives,
+        }
+    }
+
+    /// Number of samples.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Always false, since a table has at least 2 samples.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The parameter of sample `i`.
+    pub fn param(&self, i: usize) -> f64 {
+        i as f64 / (self.len() - 1) as f64
+    }
+
+    /// The four basis polynomials at sample `i`.
+    pub fn value(&self, i: usize) -> Vector4<f64> {
+        self.values[i]
+    }
+
+    /// Their derivatives at sample `i`.
+    pub fn derivative(&self, i: usize) -> Vector4<f64> {
+        self.derivatives[i]
+    }
+}
+
+/// The surface, its first partials and its mixed second partial at one
+/// vertex of a grid, from [`BezierPatch::for_each_grid_sample`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GridSample {
+    pub position: Vector3<f64>,
+    /// `∂P/∂u`
+    pub du: Vector3<f64>,
+    /// `∂P/∂v`
+    pub dv: Vector3<f64>,
+    /// The twist, `∂²P/∂u∂v`, which the normal is found from where a side
+    /// of the patch collapses to a point.
+    pub twist: Vector3<f64>,
+}
+
+impl BezierPatch {
+    // Each coordinate of the control points as a matrix P, arranged so that
+    // the surface is B(v)ᵀ · P · B(u). These are stored transposed, as
+    // Pᵀ, since a row of the grid needs B(v)ᵀ · P = (Pᵀ · B(v))ᵀ.
+    fn coordinate_matrices(&self) -> [Matrix4<f64>; 3] {
+        let p = self.points();
+        let coordinate = |k: usize| {
+            // cgmath matrices are indexed [column][row]
+            let mut m = Matrix4::zero();
+            for a in 0..4 {
+                for b in 0..4 {
+                    m[a][b] = p[a][b][k] as f64;
+                }
+            }
+            m
+        };
+        [coordinate(0), coordinate(1), coordinate(2)]
+    }
+
+    /// Calls `f(r, c, sample)` for each vertex of the grid with a row for
+    /// each sample in `rows` and a column for each in `cols`, row by row.
+    ///
+    /// The surface is `B(v)ᵀ · P · B(u)` for each coordinate, where `B` is
+    /// the Bernstein basis and `P` the control points. Each row works out
+    /// `B(v)ᵀ · P` and `B'(v)ᵀ · P` once, leaving four dot products per
+    /// vertex for the position and partials, which is much cheaper than
+    /// [`evaluate`](BezierPatch::evaluate) at each vertex.
+    ///
+    /// ```
+    /// use cgmath::{EuclideanSpace, InnerSpace};
+    ///
+    /// let patch = &teapot::control_points()[0];
+    /// let basis = teapot::BasisTable::new(5);
+    /// patch.for_each_grid_sample(&basis, &basis, |r, c, sample| {
+    ///     let exact = patch.evaluate(basis.param(c), basis.param(r));
+    ///     assert!((sample.position - exact.position.to_vec()).magnitude() < 1e-12);
+    ///     assert!((sample.du - exact.du).magnitude() < 1e-12);
+    /// });
+    /// ```
+    pub fn for_each_grid_sample<F>(&self, rows: &BasisTable, cols: &BasisTable, mut f: F)
+    where
+        F: FnMut(usize, usize, &GridSample),
+    {
+        let pt = self.coordinate_matrices();
+        for r in 0..rows.len() {
+            let (bv, dbv) = (rows.value(r), rows.derivative(r));
+            let q = [pt[0] * bv, pt[1] * bv, pt[2] * bv];
+            let qd = [pt[0] * dbv, pt[1] * dbv, pt[2] * dbv];
+            for c in 0..cols.len() {
+                let (bu, dbu) = (cols.value(c), cols.derivative(c));
+                let sample = GridSample {
+                    position: Vector3::new(q[0].dot(bu), q[1].dot(bu), q[2].dot(bu)),
+                    du: Vector3::new(q[0].dot(dbu), q[1].dot(dbu), q[2].dot(dbu)),
+                    dv: Vector3::new(qd[0].dot(bu), qd[1].dot(bu), qd[2].dot(bu)),
+                    twist: Vector3::new(qd[0].dot(dbu), qd[1].dot(dbu), qd[2].dot(dbu)),
+                };
+                f(r, c, &sample);
+            }
+        }
+    }
+}
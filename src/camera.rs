@@ -0,0 +1,29 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use cgmath::*;
+
+// OpenGL clip space has z from -w to w and y up the screen; wgpu wants z
+// from 0 to w and y down
+const OPENGL_TO_WGPU_MATRIX: Matrix4<f32> = Matrix4::new(
+    1.0, 0.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, 0.25, 0.0, 0.0, 0.0, 0.25, 1.0,
+);
+
+/// Seconds the viewer's camera takes to circle the teapot once.
+pub const ORBIT_PERIOD: f32 = 2.0 * std::f32::consts::PI;
+
+/// The viewer's transform from model to clip space at `time` seconds: a
+/// camera circling the teapot once every [`ORBIT_PERIOD`] seconds, looking
+/// at it from above.
+pub fn generate_matrix(aspect_ratio: f32, time: f32) -> Matrix4<f32> {
+    let mx_projection = perspective(Deg(35f32), aspect_ratio, 1.0, 10.0);
+    let rad = 7.0f32;
+    let v1 = rad * time.cos();
+    let v2 = rad * time.sin();
+    let mx_view = Matrix4::look_at(
+        Point3::new(v1, v2, rad / 2.),
+        Point3::new(0.0f32, 0.0, 1.4),
+        Vector3::unit_z(),
+    );
+    let mx_correction = OPENGL_TO_WGPU_MATRIX;
+    mx_correction * mx_projection * mx_view
+}
@@ -0,0 +1,321 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+//! The `triangle` binary's command line: its options, and everything it does
+//! with them short of opening a window.
+
+use crate::image::Image;
+use crate::mesh::{Mesh, Vertex};
+use crate::patch::BoundingBox;
+use crate::raster::{turntable, RenderTarget};
+use crate::shading::{load_lighting, Lighting, Material, Shading};
+use crate::teapot::{control_points, tesselate_patches, PATCH_GROUPS};
+use crate::{adaptive, camera, gltf, loader, obj, ply, stl};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// How to use the binary, printed after any error in its arguments.
+pub const USAGE: &str = "usage: triangle [validate] [--model <file>] [--tolerance <distance>] \
+     [--export <file.obj|.stl|.ply|.glb> [--ascii]] [--lighting <file>] \
+     [--shading gouraud|phong]\n       \
+     triangle render -o <file.png|.ppm> [--width <pixels>] [--height <pixels>] \
+     [--time <seconds>] [--model <file>] [--tolerance <distance>] \
+     [--lighting <file>] [--shading gouraud|phong]\n       \
+     triangle turntable [--frames <count>] [-o <directory>] [--width <pixels>] \
+     [--height <pixels>] [--model <file>] [--tolerance <distance>] [--lighting <file>] \
+     [--shading gouraud|phong]";
+
+/// Command line options.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Options {
+    /// Bezier patch file, or OBJ mesh, to draw instead of the teapot.
+    pub model: Option<String>,
+    /// Tessellate adaptively, to within this distance of the surface.
+    pub tolerance: Option<f32>,
+    /// Write the mesh to this file and exit instead of opening a window.
+    pub export: Option<String>,
+    /// Write STL files as text.
+    pub ascii: bool,
+    /// Material and light settings, instead of the defaults.
+    pub lighting: Option<String>,
+    /// Shading, instead of the lighting file's.
+    pub shading: Option<Shading>,
+    /// Check the mesh and exit instead of opening a window.
+    pub validate: bool,
+    /// Draw one frame on the CPU to `output` and exit instead of opening a
+    /// window.
+    pub render: bool,
+    /// Draw this many frames of the camera's orbit, 36 by default, to
+    /// frame_0000.png... in `output` or the current directory, and exit.
+    pub turntable: Option<u32>,
+    /// Width of the rendered images, 800 by default.
+    pub width: Option<u32>,
+    /// Height of the rendered images, 600 by default.
+    pub height: Option<u32>,
+    /// Seconds into the camera's orbit.
+    pub time: f32,
+    /// The rendered image, or the directory for the turntable's frames.
+    pub output: Option<String>,
+}
+
+impl Options {
+    /// The size of the rendered images.
+    pub fn size(&self) -> (u32, u32) {
+        (self.width.unwrap_or(800), self.height.unwrap_or(600))
+    }
+}
+
+fn parse_size(arg: Option<String>, name: &str) -> Result<u32, String> {
+    let arg = arg.ok_or(format!("{} needs a number of pixels", name))?;
+    arg.parse::<u32>()
+        .ok()
+        .filter(|n| *n > 0)
+        .ok_or(format!("invalid {} '{}'", &name[2..], arg))
+}
+
+/// Parses the arguments after the program name. The error says what is
+/// wrong with them, to print before [`USAGE`].
+///
+/// ```
+/// let options = teapot::cli::parse_args(vec!["render".to_string(), "-o".into(), "a.png".into()])
+///     .unwrap();
+/// assert!(options.render);
+/// assert_eq!(options.size(), (800, 600));
+/// ```
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.into_iter().peekable();
+    match args.peek().map(|a| a.as_str()) {
+        Some("validate") => options.validate = true,
+        Some("render") => options.render = true,
+        Some("turntable") => options.turntable = Some(36),
+        _ => (),
+    }
+    if options.validate || options.render || options.turntable.is_some() {
+        args.next();
+    }
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--model" => {
+                options.model = Some(args.next().ok_or("--model needs a file name")?);
+            }
+            "--tolerance" => {
+                let arg = args.next().ok_or("--tolerance needs a distance")?;
+                let tolerance = arg
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|t| *t > 0.0)
+                    .ok_or(format!("invalid tolerance '{}'", arg))?;
+                options.tolerance = Some(tolerance);
+            }
+            "--export" => {
+                options.export = Some(args.next().ok_or("--export needs a file name")?);
+            }
+            "--ascii" => options.ascii = true,
+            "--lighting" => {
+                options.lighting = Some(args.next().ok_or("--lighting needs a file name")?);
+            }
+            "--shading" => {
+                let arg = args.next().ok_or("--shading needs gouraud or phong")?;
+                options.shading = Some(arg.parse()?);
+            }
+            "--width" => options.width = Some(parse_size(args.next(), "--width")?),
+            "--height" => options.height = Some(parse_size(args.next(), "--height")?),
+            "--time" => {
+                let arg = args.next().ok_or("--time needs a number of seconds")?;
+                options.time = arg
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|t| t.is_finite())
+                    .ok_or(format!("invalid time '{}'", arg))?;
+            }
+            "--frames" if options.turntable.is_some() => {
+                let arg = args.next().ok_or("--frames needs a number of frames")?;
+                let frames = arg
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or(format!("invalid number of frames '{}'", arg))?;
+                options.turntable = Some(frames);
+            }
+            "-o" | "--output" => {
+                options.output = Some(args.next().ok_or("--output needs a file name")?);
+            }
+            _ => return Err(format!("unknown argument '{}'", arg)),
+        }
+    }
+    if options.render && options.output.is_none() {
+        return Err("render needs an output file, -o <file.png|.ppm>".to_string());
+    }
+    // OBJ models are triangles already, so there is nothing to tessellate
+    if let (Some(model), Some(_)) = (&options.model, options.tolerance) {
+        if has_extension(model, "obj") {
+            return Err("--tolerance only applies to patch models, not OBJ meshes".to_string());
+        }
+    }
+    Ok(options)
+}
+
+/// Whether `path` ends in `.ext`, ignoring case.
+pub fn has_extension(path: &str, ext: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case(ext))
+        .unwrap_or(false)
+}
+
+/// The `--model` as a triangle mesh, either read from an OBJ file or by
+/// tessellating its patches, or the teapot's without one.
+pub fn load_mesh(options: &Options) -> Result<Mesh, String> {
+    let num_rows = 18;
+    let num_cols = 23;
+    let mesh = match &options.model {
+        Some(path) if has_extension(path, "obj") => {
+            obj::load_obj(path).map_err(|e| format!("{}: {}", path, e))?
+        }
+        _ => {
+            let patches = match &options.model {
+                Some(path) => loader::load_patches(path).map_err(|e| format!("{}: {}", path, e))?,
+                None => control_points(),
+            };
+            match options.tolerance {
+                Some(tolerance) => adaptive::tesselate_adaptive(&patches, tolerance),
+                None => tesselate_patches(&patches, num_rows, num_cols),
+            }
+        }
+    };
+    Ok(mesh)
+}
+
+/// The `--lighting` file, or the defaults without one, with the
+/// `--shading`.
+pub fn read_lighting(options: &Options) -> Result<Lighting, String> {
+    let mut lighting = match &options.lighting {
+        Some(path) => load_lighting(path).map_err(|e| format!("{}: {}", path, e))?,
+        None => Lighting::default(),
+    };
+    if let Some(shading) = options.shading {
+        lighting.shading = shading;
+    }
+    Ok(lighting)
+}
+
+/// Writes the mesh to `path` in the format given by its extension: OBJ,
+/// STL, PLY or glTF binary. `groups` name the parts of the mesh for glTF.
+pub fn export(
+    mesh: &Mesh,
+    groups: &[(&str, Range<usize>)],
+    path: &str,
+    ascii: bool,
+    material: &Material,
+) -> std::io::Result<()> {
+    let ext = Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    match ext.as_str() {
+        "obj" => obj::save_obj(mesh, path),
+        "stl" if ascii => stl::save_stl_ascii(mesh, path),
+        "stl" => stl::save_stl(mesh, path),
+        "ply" => ply::save_ply(mesh, &ply::PlyOptions::default(), path),
+        "glb" => gltf::save_glb(mesh, groups, material, path),
+        _ => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("don't know how to write '.{}' files", ext),
+        )),
+    }
+}
+
+/// Writes the mesh to the `--export` file, with the teapot's parts as
+/// separate groups. Other models are exported as a single part.
+pub fn export_mesh(options: &Options, mesh: &Mesh, material: &Material) -> Result<(), String> {
+    let path = options.export.as_deref().ok_or("nothing to export to")?;
+    let groups = match options.model {
+        Some(_) => vec![("model", 0..mesh.patch_ranges.len())],
+        None => PATCH_GROUPS.to_vec(),
+    };
+    export(mesh, &groups, path, options.ascii, material).map_err(|e| format!("{}: {}", path, e))
+}
+
+/// Scales and moves the mesh to fill about as much of the view as the
+/// teapot. Meshes can be any size and anywhere, and the camera only looks
+/// at the teapot.
+pub fn fit_to_view(mesh: &mut Mesh) {
+    let points = mesh
+        .vertices
+        .iter()
+        .map(|v| cgmath::Point3::new(v[0], v[1], v[2]))
+        .collect::<Vec<_>>();
+    let bbox = match BoundingBox::from_points(points.iter()) {
+        Some(bbox) => bbox,
+        None => return,
+    };
+    let size = cgmath::InnerSpace::magnitude(bbox.size());
+    if size == 0.0 || !size.is_finite() {
+        return;
+    }
+    let center = bbox.center();
+    let scale = 7.0 / size;
+    for v in mesh.vertices.iter_mut() {
+        v[0] = (v[0] - center.x) * scale;
+        v[1] = (v[1] - center.y) * scale;
+        v[2] = (v[2] - center.z) * scale + 1.4;
+    }
+}
+
+/// Draws the frame the viewer would show at `--time` on the CPU, so this
+/// works without a display or GPU.
+pub fn render_frame(
+    options: &Options,
+    vertices: &[Vertex],
+    indices: &[u32],
+    lighting: &Lighting,
+) -> Image {
+    let (width, height) = options.size();
+    let transform = camera::generate_matrix(width as f32 / height as f32, options.time);
+    let mut target = RenderTarget::new(width, height);
+    target.set_lighting(*lighting);
+    target.draw_indexed(vertices, indices, &transform);
+    target.to_image()
+}
+
+/// Renders the frame and saves it to the `-o` file, as PNG or PPM
+/// depending on its extension.
+pub fn save_render(
+    options: &Options,
+    vertices: &[Vertex],
+    indices: &[u32],
+    lighting: &Lighting,
+) -> Result<(), String> {
+    let path = options
+        .output
+        .as_deref()
+        .ok_or("render needs an output file")?;
+    render_frame(options, vertices, indices, lighting)
+        .save(path)
+        .map_err(|e| format!("{}: {}", path, e))
+}
+
+/// Renders one orbit of the camera at a fixed timestep and saves the frames
+/// as frame_0000.png, frame_0001.png... for video tools, in the `-o`
+/// directory or the current one. Returns the directory.
+pub fn save_turntable(
+    options: &Options,
+    vertices: &[Vertex],
+    indices: &[u32],
+    lighting: &Lighting,
+) -> Result<PathBuf, String> {
+    let dir = PathBuf::from(options.output.as_deref().unwrap_or("."));
+    let frames = options.turntable.unwrap_or(36);
+    let (width, height) = options.size();
+    let images = turntable(vertices, indices, lighting, width, height, frames);
+    let result = std::fs::create_dir_all(&dir).and_then(|_| {
+        for (k, image) in images.enumerate() {
+            image.save(dir.join(format!("frame_{:04}.png", k)))?;
+        }
+        Ok(())
+    });
+    result.map_err(|e| format!("{}: {}", dir.display(), e))?;
+    Ok(dir)
+}
@@ -0,0 +1,260 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use crate::mesh::Mesh;
+use crate::shading::Material;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::ops::Range;
+use std::path::Path;
+
+// glTF constants
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const TRIANGLES: u32 = 4;
+
+/// Writes `mesh` as a binary glTF 2.0 (`.glb`) file.
+///
+/// The vertices are shared by one glTF mesh, with a primitive for each of
+/// `groups`, a name and a range of patches such as those in
+/// [`PATCH_GROUPS`](crate::PATCH_GROUPS). The name is stored in the
+/// primitive's `extras`. A mesh that doesn't know where its patches are is
+/// written as a single primitive.
+///
+/// Every primitive uses `material`, such as the viewer's: its colour, with
+/// the specular strength in `KHR_materials_specular` and a roughness
+/// equivalent to its Blinn-Phong exponent. Both of those are clamped to the
+/// 0 to 1 glTF allows, and a material with a colour, specular strength or
+/// roughness that isn't a finite number is an `InvalidInput` error.
+///
+/// glTF is y-up, so the node rotates the teapot's z axis up onto y.
+///
+/// Panics if a group names patches the mesh doesn't have.
+pub fn write_glb<W: Write>(
+    mesh: &Mesh,
+    groups: &[(&str, Range<usize>)],
+    material: &Material,
+    out: W,
+) -> io::Result<()> {
+    let mut out = out;
+    let n = mesh.num_vertices();
+
+    // NaN and infinity aren't JSON numbers
+    let finite = material.color.iter().all(|c| c.is_finite())
+        && material.specular.is_finite()
+        && roughness(material.shininess).is_finite();
+    if !finite {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the material's colour, specular and roughness must be finite",
+        ));
+    }
+
+    // binary buffer: positions, normals, uvs, then indices
+    let mut bin: Vec<u8> = Vec::with_capacity(32 * n + 4 * mesh.indices.len());
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+    for v in mesh.vertices.iter() {
+        for k in 0..3 {
+            min[k] = min[k].min(v[k]);
+            max[k] = max[k].max(v[k]);
+            bin.extend_from_slice(&v[k].to_le_bytes());
+        }
+    }
+    for x in mesh.normals.iter().flatten() {
+        bin.extend_from_slice(&x.to_le_bytes());
+    }
+    // glTF texture coordinates start at the top left
+    for t in mesh.uvs.iter() {
+        bin.extend_from_slice(&t[0].to_le_bytes());
+        bin.extend_from_slice(&(1.0 - t[1]).to_le_bytes());
+    }
+    for i in mesh.indices.iter() {
+        bin.extend_from_slice(&i.to_le_bytes());
+    }
+    if n == 0 {
+        min = [0.0; 3];
+        max = [0.0; 3];
+    }
+
+    let primitives: Vec<(&str, Range<usize>)> = if mesh.patch_ranges.is_empty() {
+        vec![("mesh", 0..mesh.indices.len())]
+    } else {
+        groups
+            .iter()
+            .filter(|(_, patches)| !patches.is_empty())
+            .map(|(name, patches)| {
+                let start = mesh.patch_ranges[patches.start].start;
+                let end = mesh.patch_ranges[patches.end - 1].end;
+                (*name, start..end)
+            })
+            .collect()
+    };
+
+    let json = gltf_json(
+        n,
+        mesh.indices.len(),
+        bin.len(),
+        min,
+        max,
+        &primitives,
+        material,
+    );
+    let mut json = json.into_bytes();
+    // chunks are padded to 4 bytes
+    json.resize(json.len() + padding(json.len()), b' ');
+    bin.resize(bin.len() + padding(bin.len()), 0);
+
+    let length = 12 + 8 + json.len() + 8 + bin.len();
+    out.write_all(b"glTF")?;
+    out.write_all(&2u32.to_le_bytes())?;
+    out.write_all(&(length as u32).to_le_bytes())?;
+    out.write_all(&(json.len() as u32).to_le_bytes())?;
+    out.write_all(b"JSON")?;
+    out.write_all(&json)?;
+    out.write_all(&(bin.len() as u32).to_le_bytes())?;
+    out.write_all(b"BIN\0")?;
+    out.write_all(&bin)?;
+    out.flush()
+}
+
+/// Writes `mesh` to a `.glb` file at `path`, as [`write_glb`] does.
+pub fn save_glb<P: AsRef<Path>>(
+    mesh: &Mesh,
+    groups: &[(&str, Range<usize>)],
+    material: &Material,
+    path: P,
+) -> io::Result<()> {
+    write_glb(mesh, groups, material, BufWriter::new(File::create(path)?))
+}
+
+fn padding(len: usize) -> usize {
+    (4 - len % 4) % 4
+}
+
+// Blinn-Phong exponent to GGX roughness, using alpha = sqrt(2 / (n + 2))
+// and roughness = sqrt(alpha)
+fn roughness(shininess: f32) -> f32 {
+    (2.0 / (shininess + 2.0)).sqrt().sqrt()
+}
+
+// `s` as a quoted JSON string
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if c < ' ' => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+// The JSON chunk, for num_vertices vertices and num_indices indices laid out
+// in a buffer of buffer_len bytes as write_glb does, and the given
+// primitives' ranges of indices, all with the given material
+fn gltf_json(
+    num_vertices: usize,
+    num_indices: usize,
+    buffer_len: usize,
+    min: [f32; 3],
+    max: [f32; 3],
+    primitives: &[(&str, Range<usize>)],
+    material: &Material,
+) -> String {
+    let n = num_vertices;
+    let index_offset = 32 * n;
+
+    let mut accessors = vec![
+        format!(
+            r#"{{"bufferView":0,"componentType":{},"count":{},"type":"VEC3","min":[{},{},{}],"max":[{},{},{}]}}"#,
+            FLOAT, n, min[0], min[1], min[2], max[0], max[1], max[2]
+        ),
+        format!(
+            r#"{{"bufferView":1,"componentType":{},"count":{},"type":"VEC3"}}"#,
+            FLOAT, n
+        ),
+        format!(
+            r#"{{"bufferView":2,"componentType":{},"count":{},"type":"VEC2"}}"#,
+            FLOAT, n
+        ),
+    ];
+    let mut prims = Vec::with_capacity(primitives.len());
+    for (name, range) in primitives.iter() {
+        prims.push(format!(
+            r#"{{"attributes":{{"POSITION":0,"NORMAL":1,"TEXCOORD_0":2}},"indices":{},"material":0,"mode":{},"extras":{{"name":{}}}}}"#,
+            accessors.len(),
+            TRIANGLES,
+            json_string(name)
+        ));
+        accessors.push(format!(
+            r#"{{"bufferView":3,"byteOffset":{},"componentType":{},"count":{},"type":"SCALAR"}}"#,
+            4 * range.start,
+            UNSIGNED_INT,
+            range.len()
+        ));
+    }
+
+    let buffer_views = [
+        format!(
+            r#"{{"buffer":0,"byteOffset":0,"byteLength":{},"target":{}}}"#,
+            12 * n,
+            ARRAY_BUFFER
+        ),
+        format!(
+            r#"{{"buffer":0,"byteOffset":{},"byteLength":{},"target":{}}}"#,
+            12 * n,
+            12 * n,
+            ARRAY_BUFFER
+        ),
+        format!(
+            r#"{{"buffer":0,"byteOffset":{},"byteLength":{},"target":{}}}"#,
+            24 * n,
+            8 * n,
+            ARRAY_BUFFER
+        ),
+        format!(
+            r#"{{"buffer":0,"byteOffset":{},"byteLength":{},"target":{}}}"#,
+            index_offset,
+            4 * num_indices,
+            ELEMENT_ARRAY_BUFFER
+        ),
+    ];
+
+    let roughness = roughness(material.shininess).min(1.0);
+    let specular = material.specular.clamp(0.0, 1.0);
+    let color = material.color;
+    let material = format!(
+        r#"{{"name":"teapot","pbrMetallicRoughness":{{"baseColorFactor":[{},{},{},1],"metallicFactor":0,"roughnessFactor":{}}},"extensions":{{"KHR_materials_specular":{{"specularFactor":{}}}}}}}"#,
+        color[0], color[1], color[2], roughness, specular
+    );
+
+    // rotate -90 degrees about x, taking z up to y up
+    let half = std::f32::consts::FRAC_1_SQRT_2;
+    let node = format!(
+        r#"{{"name":"teapot","mesh":0,"rotation":[{},0,0,{}]}}"#,
+        -half, half
+    );
+
+    format!(
+        concat!(
+            r#"{{"asset":{{"version":"2.0","generator":"teapot"}},"#,
+            r#""extensionsUsed":["KHR_materials_specular"],"#,
+            r#""scene":0,"scenes":[{{"nodes":[0]}}],"nodes":[{}],"#,
+            r#""meshes":[{{"name":"teapot","primitives":[{}]}}],"#,
+            r#""materials":[{}],"accessors":[{}],"bufferViews":[{}],"#,
+            r#""buffers":[{{"byteLength":{}}}]}}"#
+        ),
+        node,
+        prims.join(","),
+        material,
+        accessors.join(","),
+        buffer_views.join(","),
+        buffer_len
+    )
+}
@@ -0,0 +1,215 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use crate::loader::LoadError;
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// An 8 bit RGB image, stored row by row from the top left.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    /// A black image.
+    pub fn new(width: u32, height: u32) -> Image {
+        Image {
+            width,
+            height,
+            pixels: vec![[0, 0, 0]; width as usize * height as usize],
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The pixels, row by row from the top left.
+    pub fn pixels(&self) -> &[[u8; 3]] {
+        &self.pixels
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 3] {
+        self.pixels[self.index(x, y)]
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, rgb: [u8; 3]) {
+        let i = self.index(x, y);
+        self.pixels[i] = rgb;
+    }
+
+    // In usize, as an image can have more pixels than fit in a u32
+    fn index(&self, x: u32, y: u32) -> usize {
+        y as usize * self.width as usize + x as usize
+    }
+
+    /// Number of pixels where any channel differs from `other` by more than
+    /// `tolerance`, for comparing renders with golden images. Images of
+    /// different sizes differ everywhere.
+    pub fn differing_pixels(&self, other: &Image, tolerance: u8) -> usize {
+        if (self.width, self.height) != (other.width, other.height) {
+            return self.pixels.len().max(other.pixels.len());
+        }
+        self.pixels
+            .iter()
+            .zip(other.pixels.iter())
+            .filter(|(a, b)| (0..3).any(|k| (a[k] as i32 - b[k] as i32).abs() > tolerance as i32))
+            .count()
+    }
+
+    /// Writes the image as a binary PPM (`P6`) file.
+    pub fn write_ppm<W: Write>(&self, out: W) -> io::Result<()> {
+        let mut out = out;
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        for p in self.pixels.iter() {
+            out.write_all(p)?;
+        }
+        out.flush()
+    }
+
+    /// Writes the image as a PNG file.
+    pub fn write_png<W: Write>(&self, out: W) -> io::Result<()> {
+        let mut out = out;
+        out.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&self.width.to_be_bytes());
+        header.extend_from_slice(&self.height.to_be_bytes());
+        // 8 bits per channel, RGB, no interlacing
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+        write_chunk(&mut out, b"IHDR", &header)?;
+
+        // each row starts with filter type 0, none
+        let mut rows = Vec::with_capacity(self.height as usize * (1 + 3 * self.width as usize));
+        for row in self.pixels.chunks(self.width.max(1) as usize) {
+            rows.push(0);
+            for p in row {
+                rows.extend_from_slice(p);
+            }
+        }
+        write_chunk(&mut out, b"IDAT", &compress_to_vec_zlib(&rows, 6))?;
+        write_chunk(&mut out, b"IEND", &[])?;
+        out.flush()
+    }
+
+    /// Writes the image to `path` as PNG or PPM, depending on its
+    /// extension.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        // before creating the file, so a bad extension doesn't leave an
+        // empty one behind
+        let write = match ext.as_str() {
+            "png" => Image::write_png,
+            "ppm" => Image::write_ppm,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("don't know how to write '.{}' files", ext),
+                ))
+            }
+        };
+        write(self, BufWriter::new(File::create(path)?))
+    }
+}
+
+/// Reads a binary PPM (`P6`) file with 8 bit channels, such as a golden
+/// image written by [`Image::write_ppm`].
+pub fn parse_ppm(data: &[u8]) -> Result<Image, LoadError> {
+    let invalid = |message: &str| LoadError::Parse {
+        line: 0,
+        message: message.to_string(),
+    };
+
+    // four whitespace separated fields, then a single whitespace byte
+    let mut fields = Vec::new();
+    let mut pos = 0;
+    while fields.len() < 4 {
+        while pos < data.len() && data[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        if pos < data.len() && data[pos] == b'#' {
+            while pos < data.len() && data[pos] != b'\n' {
+                pos += 1;
+            }
+            continue;
+        }
+        let start = pos;
+        while pos < data.len() && !data[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        if start == pos {
+            return Err(invalid("PPM header ends early"));
+        }
+        fields.push(String::from_utf8_lossy(&data[start..pos]).into_owned());
+    }
+    pos += 1;
+
+    if fields[0] != "P6" {
+        return Err(invalid("not a binary PPM file"));
+    }
+    let number = |s: &str| s.parse::<u32>().map_err(|_| invalid("invalid PPM header"));
+    let (width, height, max) = (
+        number(&fields[1])?,
+        number(&fields[2])?,
+        number(&fields[3])?,
+    );
+    if max != 255 {
+        return Err(invalid("only 8 bit PPM files are supported"));
+    }
+    let size = 3 * width as usize * height as usize;
+    if data.len() < pos + size {
+        return Err(invalid("PPM pixel data ends early"));
+    }
+    let pixels = data[pos..pos + size]
+        .chunks(3)
+        .map(|p| [p[0], p[1], p[2]])
+        .collect();
+    Ok(Image {
+        width,
+        height,
+        pixels,
+    })
+}
+
+/// Reads a PPM file with [`parse_ppm`].
+pub fn load_ppm<P: AsRef<Path>>(path: P) -> Result<Image, LoadError> {
+    parse_ppm(&std::fs::read(path)?)
+}
+
+// Writes a PNG chunk: its length, type, data and CRC
+fn write_chunk<W: Write>(out: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+    let crc = crc32(kind.iter().chain(data.iter()));
+    out.write_all(&crc.to_be_bytes())
+}
+
+// The CRC-32 that PNG and zip use
+fn crc32<'a, I: Iterator<Item = &'a u8>>(bytes: I) -> u32 {
+    let mut crc = !0u32;
+    for &b in bytes {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
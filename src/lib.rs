@@ -0,0 +1,111 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+//! Tessellation of [Martin Newell's teapot](https://en.wikipedia.org/wiki/Utah_teapot).
+//!
+//! The teapot is defined as 32 cubic Bézier patches. [`control_points`]
+//! returns them as a [`PatchSet`], [`tesselate_patch`] turns one
+//! [`BezierPatch`] into a regular grid of triangles and [`create_vertices`]
+//! does the whole teapot:
+//!
+//! ```
+//! let mesh = teapot::create_vertices(18, 23);
+//! // the 8 patches of the lid knob and bottom have a single vertex where
+//! // their first row collapses to a point
+//! assert_eq!(mesh.num_vertices(), 32 * 18 * 23 - 8 * (23 - 1));
+//! ```
+//!
+//! Rather than use the same grid for every patch, [`tesselate_adaptive`]
+//! picks each patch's resolution from a limit on the distance between the
+//! triangles and the surface. Neighbouring patches are stitched together
+//! along their shared edges, so there are no cracks between them.
+//!
+//! The patches are tessellated independently, so vertices along the seams
+//! between them are duplicated. [`Mesh::weld`] merges them into a connected
+//! mesh.
+//!
+//! [`BezierPatch::evaluate`] gives the exact surface at any `(u, v)`: its
+//! position, normal and derivatives, and from those its curvature.
+//! [`PatchSet::intersect_ray`] finds where a [`Ray`] hits it, for picking
+//! and ray casting. [`PatchSet::mass_properties`] integrates its area,
+//! volume, centroid and inertia tensor, and [`Mesh::mass_properties`] does
+//! the same for a tessellation of it.
+//!
+//! [`save_obj`] writes a mesh out as a Wavefront OBJ file for other tools,
+//! with a group for each patch. [`save_stl`] writes STL for 3D printing and
+//! [`save_ply`] writes PLY for point cloud tools. [`save_glb`] writes binary
+//! glTF for web viewers and engines, with the teapot's body, handle, spout,
+//! lid and bottom as separate primitives.
+//!
+//! Other patch models, such as the teacup and teaspoon, can be read with
+//! [`load_patches`], and triangle meshes from OBJ files with [`load_obj`].
+//!
+//! [`RenderTarget`] draws the viewer's vertex and index buffers on the CPU,
+//! with the same transform from [`generate_matrix`] and the same lighting,
+//! into an [`Image`] that can be saved as PNG or PPM. It needs no window or
+//! GPU, so renders can be checked against golden images in tests, and
+//! [`turntable`] renders a frame sequence of the camera's whole orbit.
+//! Both shade with a [`Lighting`], a [`Material`] and [`Light`] that can be
+//! read from a file with [`load_lighting`], and the viewer uploads the same
+//! in its [`Locals`] uniform block. Its [`Shading`] picks between lighting
+//! the vertices and lighting every pixel.
+//!
+//! The [`cli`] module is the `triangle` binary's command line, everything
+//! it does short of opening a window.
+//!
+//! This crate has no windowing or GPU dependencies unless the `viewer`
+//! feature (on by default) is enabled for the `triangle` binary. The
+//! `parallel` feature tessellates patches on several threads with rayon.
+
+mod adaptive;
+mod basis;
+mod camera;
+pub mod cli;
+mod gltf;
+mod image;
+mod loader;
+mod mass;
+mod mesh;
+mod obj;
+mod patch;
+mod ply;
+mod raster;
+mod ray;
+mod shading;
+mod stitch;
+mod stl;
+mod surface;
+mod tangent;
+mod teapot;
+mod validate;
+mod weld;
+
+pub use crate::adaptive::{
+    adaptive_triangle_count, patch_resolution, tesselate_adaptive, tolerance_for_budget,
+    MAX_RESOLUTION,
+};
+pub use crate::basis::{BasisTable, GridSample};
+pub use crate::camera::{generate_matrix, ORBIT_PERIOD};
+pub use crate::gltf::{save_glb, write_glb};
+pub use crate::image::{load_ppm, parse_ppm, Image};
+pub use crate::loader::{load_patches, parse_bpt, parse_newell, LoadError};
+pub use crate::mass::MassProperties;
+pub use crate::mesh::{Mesh, Vertex};
+pub use crate::obj::{load_obj, parse_obj, save_obj, write_obj};
+pub use crate::patch::{BezierPatch, BoundingBox, PatchSet, Side};
+pub use crate::ply::{save_ply, write_ply, PlyOptions};
+pub use crate::raster::{render, turntable, RenderTarget};
+pub use crate::ray::{Ray, RayHit};
+pub use crate::shading::{
+    load_lighting, parse_lighting, Light, Lighting, Locals, Material, Shading,
+};
+pub use crate::stitch::{
+    patch_adjacency, tesselate_stitched, tesselate_stitched_serial, Neighbour,
+};
+pub use crate::stl::{save_stl, save_stl_ascii, write_stl, write_stl_ascii};
+pub use crate::surface::{Curvature, SurfacePoint};
+pub use crate::teapot::{
+    control_points, create_vertices, tesselate_patch, tesselate_patch_with, tesselate_patches,
+    NUM_PATCHES, PATCH_GROUPS,
+};
+pub use crate::validate::{ValidateOptions, ValidationReport};
+pub use crate::weld::WeldOptions;
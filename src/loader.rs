@@ -0,0 +1,223 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use crate::patch::{BezierPatch, PatchSet};
+use cgmath::*;
+use std::fmt;
+use std::path::Path;
+
+/// Something that went wrong reading a patch, mesh or image file.
+#[derive(Debug)]
+pub enum LoadError {
+    Io(std::io::Error),
+    /// Malformed input. `line` is 1-based, or 0 for problems with the file
+    /// as a whole, such as ending early.
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "{}", e),
+            LoadError::Parse { line: 0, message } => write!(f, "{}", message),
+            LoadError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io(e) => Some(e),
+            LoadError::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for LoadError {
+    fn from(e: std::io::Error) -> LoadError {
+        LoadError::Io(e)
+    }
+}
+
+/// Reads a patch file. Files ending in `.bpt` are read with [`parse_bpt`],
+/// anything else with [`parse_newell`].
+pub fn load_patches<P: AsRef<Path>>(path: P) -> Result<PatchSet, LoadError> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)?;
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("bpt") => parse_bpt(&text),
+        _ => parse_newell(&text),
+    }
+}
+
+/// Parses Newell's original format: the number of patches, one line of 16
+/// 1-based vertex indices per patch, the number of vertices and then one
+/// `x, y, z` line per vertex. Commas are optional.
+///
+/// ```
+/// let text = "1\n1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16\n16\n\
+///             0,0,0\n1,0,0\n2,0,0\n3,0,0\n0,1,0\n1,1,0\n2,1,0\n3,1,0\n\
+///             0,2,0\n1,2,0\n2,2,0\n3,2,0\n0,3,0\n1,3,0\n2,3,0\n3,3,0\n";
+/// let patches = teapot::parse_newell(text).unwrap();
+/// assert_eq!(patches.len(), 1);
+/// assert_eq!(patches[0][(1, 2)], cgmath::Point3::new(2.0, 1.0, 0.0));
+/// ```
+pub fn parse_newell(text: &str) -> Result<PatchSet, LoadError> {
+    let mut lines = Lines::new(text);
+
+    let num_patches = lines.count("patch")?;
+    // the counts aren't trusted until that many lines have been read, so
+    // they don't size any allocations
+    let mut indices = Vec::new();
+    for _ in 0..num_patches {
+        let (line, fields) = lines.fields("patch")?;
+        if fields.len() != 16 {
+            return Err(parse_error(
+                line,
+                format!("expected 16 vertex indices, found {}", fields.len()),
+            ));
+        }
+        let mut patch = [0usize; 16];
+        for (i, f) in fields.iter().enumerate() {
+            patch[i] = parse_field(line, f, "vertex index")?;
+        }
+        indices.push((line, patch));
+    }
+
+    let num_vertices = lines.count("vertex")?;
+    let mut vertices = Vec::new();
+    for _ in 0..num_vertices {
+        vertices.push(lines.point()?);
+    }
+    lines.finish()?;
+
+    let mut patches = PatchSet::new(Vec::with_capacity(indices.len()));
+    for (line, patch) in indices {
+        let mut points = [[Point3::new(0.0, 0.0, 0.0); 4]; 4];
+        for (i, &index) in patch.iter().enumerate() {
+            if index == 0 || index > vertices.len() {
+                return Err(parse_error(
+                    line,
+                    format!(
+                        "vertex index {} out of range 1..={}",
+                        index,
+                        vertices.len()
+                    ),
+                ));
+            }
+            points[i / 4][i % 4] = vertices[index - 1];
+        }
+        patches.push(BezierPatch::new(points));
+    }
+    Ok(patches)
+}
+
+/// Parses the `.bpt` format: the number of patches, then for each patch a
+/// line with its degree in `u` and `v` followed by its control points, one
+/// `x y z` per line. Only bicubic (`3 3`) patches are supported.
+pub fn parse_bpt(text: &str) -> Result<PatchSet, LoadError> {
+    let mut lines = Lines::new(text);
+
+    let num_patches = lines.count("patch")?;
+    let mut patches = PatchSet::new(Vec::new());
+    for _ in 0..num_patches {
+        let (line, fields) = lines.fields("patch")?;
+        if fields.len() != 2 {
+            return Err(parse_error(
+                line,
+                format!("expected patch degrees, found {} fields", fields.len()),
+            ));
+        }
+        let du: usize = parse_field(line, fields[0], "degree")?;
+        let dv: usize = parse_field(line, fields[1], "degree")?;
+        if du != 3 || dv != 3 {
+            return Err(parse_error(
+                line,
+                format!("only bicubic patches are supported, found degree {} {}", du, dv),
+            ));
+        }
+
+        let mut points = [[Point3::new(0.0, 0.0, 0.0); 4]; 4];
+        for row in points.iter_mut() {
+            for p in row.iter_mut() {
+                *p = lines.point()?;
+            }
+        }
+        patches.push(BezierPatch::new(points));
+    }
+    lines.finish()?;
+    Ok(patches)
+}
+
+pub(crate) fn parse_error(line: usize, message: String) -> LoadError {
+    LoadError::Parse { line, message }
+}
+
+pub(crate) fn parse_field<T: std::str::FromStr>(
+    line: usize,
+    field: &str,
+    what: &str,
+) -> Result<T, LoadError> {
+    field
+        .parse()
+        .map_err(|_| parse_error(line, format!("invalid {} '{}'", what, field)))
+}
+
+// Non-blank lines split into fields, remembering line numbers for errors.
+struct Lines<'a> {
+    iter: std::iter::Enumerate<std::str::Lines<'a>>,
+}
+
+impl<'a> Lines<'a> {
+    fn new(text: &'a str) -> Lines<'a> {
+        Lines {
+            iter: text.lines().enumerate(),
+        }
+    }
+
+    fn fields(&mut self, what: &str) -> Result<(usize, Vec<&'a str>), LoadError> {
+        for (i, l) in &mut self.iter {
+            let fields: Vec<&str> = l
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|f| !f.is_empty())
+                .collect();
+            if !fields.is_empty() {
+                return Ok((i + 1, fields));
+            }
+        }
+        Err(parse_error(0, format!("unexpected end of file reading {}", what)))
+    }
+
+    fn count(&mut self, what: &str) -> Result<usize, LoadError> {
+        let (line, fields) = self.fields(&format!("{} count", what))?;
+        if fields.len() != 1 {
+            return Err(parse_error(
+                line,
+                format!("expected {} count, found {} fields", what, fields.len()),
+            ));
+        }
+        parse_field(line, fields[0], &format!("{} count", what))
+    }
+
+    fn point(&mut self) -> Result<Point3<f32>, LoadError> {
+        let (line, fields) = self.fields("vertex")?;
+        if fields.len() != 3 {
+            return Err(parse_error(
+                line,
+                format!("expected x, y, z, found {} fields", fields.len()),
+            ));
+        }
+        Ok(Point3::new(
+            parse_field(line, fields[0], "coordinate")?,
+            parse_field(line, fields[1], "coordinate")?,
+            parse_field(line, fields[2], "coordinate")?,
+        ))
+    }
+
+    fn finish(&mut self) -> Result<(), LoadError> {
+        match self.fields("") {
+            Ok((line, _)) => Err(parse_error(line, "unexpected data after last patch".to_string())),
+            Err(_) => Ok(()),
+        }
+    }
+}
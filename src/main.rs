@@ -1,26 +1,95 @@
 ﻿// Teapot demo © 2020 RustyTriangles LLC
 
-#[path = "teapot.rs"]
-mod teapot;
-
+use teapot::cli;
 use zerocopy::AsBytes;
 
-const OPENGL_TO_WGPU_MATRIX: cgmath::Matrix4<f32> = cgmath::Matrix4::new(
-    1.0, 0.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, 0.25, 0.0, 0.0, 0.0, 0.25, 1.0,
-);
-
-fn generate_matrix(aspect_ratio: f32, time: f32) -> cgmath::Matrix4<f32> {
-    let mx_projection = cgmath::perspective(cgmath::Deg(35f32), aspect_ratio, 1.0, 10.0);
-    let rad = 7.0f32;
-    let v1 = rad*time.cos();
-    let v2 = rad*time.sin();
-    let mx_view = cgmath::Matrix4::look_at(
-        cgmath::Point3::new(v1, v2, rad/2.),
-        cgmath::Point3::new(0.0f32, 0.0, 1.4),
-        cgmath::Vector3::unit_z(),
-    );
-    let mx_correction = OPENGL_TO_WGPU_MATRIX;
-    mx_correction * mx_projection * mx_view
+// Where the fields of teapot::Vertex are, for the vertex shader
+const VERTEX_ATTRIBUTES: [wgpu::VertexAttributeDescriptor; 4] = [
+    // position [x,y,z,w] f32
+    wgpu::VertexAttributeDescriptor {
+        format: wgpu::VertexFormat::Float4,
+        offset: teapot::Vertex::POS_OFFSET as wgpu::BufferAddress,
+        shader_location: 0,
+    },
+    // normal [x,y,z] f32
+    wgpu::VertexAttributeDescriptor {
+        format: wgpu::VertexFormat::Float3,
+        offset: teapot::Vertex::NORMAL_OFFSET as wgpu::BufferAddress,
+        shader_location: 1,
+    },
+    // uv [u,v] f32
+    wgpu::VertexAttributeDescriptor {
+        format: wgpu::VertexFormat::Float2,
+        offset: teapot::Vertex::UV_OFFSET as wgpu::BufferAddress,
+        shader_location: 2,
+    },
+    // tangent [x,y,z] and handedness f32
+    wgpu::VertexAttributeDescriptor {
+        format: wgpu::VertexFormat::Float4,
+        offset: teapot::Vertex::TANGENT_OFFSET as wgpu::BufferAddress,
+        shader_location: 3,
+    },
+];
+
+// Colours the C key cycles the material through, starting with the default
+const MATERIAL_COLORS: [[f32; 3]; 5] = [
+    [0.5, 0.25, 1.0],
+    [0.8, 0.1, 0.1],
+    [0.1, 0.6, 0.2],
+    [1.0, 0.7, 0.2],
+    [0.9, 0.9, 0.9],
+];
+
+// Swings the light `azimuth` degrees around the teapot's vertical axis and
+// raises it `elevation` degrees, stopping short of straight up or down
+fn move_light(light: &mut teapot::Light, azimuth: f32, elevation: f32) {
+    let [x, y, z] = light.direction;
+    let r = (x * x + y * y + z * z).sqrt();
+    let theta = y.atan2(x) + azimuth.to_radians();
+    let phi = (z / r).asin() + elevation.to_radians();
+    let limit = 85f32.to_radians();
+    let phi = if phi > limit { limit } else { phi.max(-limit) };
+    light.direction = [
+        r * phi.cos() * theta.cos(),
+        r * phi.cos() * theta.sin(),
+        r * phi.sin(),
+    ];
+}
+
+// The lighting keyboard shortcuts. Returns whether `key` is one of them.
+fn adjust_lighting(lighting: &mut teapot::Lighting, key: winit::event::VirtualKeyCode) -> bool {
+    use winit::event::VirtualKeyCode as Key;
+    let material = &mut lighting.material;
+    let step = 0.05;
+    match key {
+        Key::Q => material.ambient += step,
+        Key::A => material.ambient = (material.ambient - step).max(0.0),
+        Key::W => material.diffuse += step,
+        Key::S => material.diffuse = (material.diffuse - step).max(0.0),
+        Key::E => material.specular += step,
+        Key::D => material.specular = (material.specular - step).max(0.0),
+        Key::R => material.shininess *= 1.25,
+        Key::F => material.shininess = (material.shininess / 1.25).max(1.0),
+        Key::C => {
+            let next = MATERIAL_COLORS
+                .iter()
+                .position(|c| *c == material.color)
+                .map_or(0, |i| (i + 1) % MATERIAL_COLORS.len());
+            material.color = MATERIAL_COLORS[next];
+        }
+        Key::Left => move_light(&mut lighting.light, -15.0, 0.0),
+        Key::Right => move_light(&mut lighting.light, 15.0, 0.0),
+        Key::Up => move_light(&mut lighting.light, 0.0, 15.0),
+        Key::Down => move_light(&mut lighting.light, 0.0, -15.0),
+        Key::P => {
+            lighting.shading = match lighting.shading {
+                teapot::Shading::Gouraud => teapot::Shading::Phong,
+                teapot::Shading::Phong => teapot::Shading::Gouraud,
+            }
+        }
+        _ => return false,
+    }
+    true
 }
 
 fn main() {
@@ -30,6 +99,73 @@ fn main() {
     };
 
     env_logger::init();
+    let options = cli::parse_args(std::env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        eprintln!("{}", cli::USAGE);
+        std::process::exit(1);
+    });
+    let mut lighting = cli::read_lighting(&options).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+
+    // get vertex coordinates, either from a triangle mesh or by tessellating
+    // patches
+    let mut mesh = cli::load_mesh(&options).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    println!(
+        "num verts = {0}, num indices = {1}",
+        mesh.vertices.len(),
+        mesh.indices.len()
+    );
+
+    if options.validate {
+        // exits with an error for any problem other than open boundaries
+        let report = mesh.validate(&teapot::ValidateOptions::default());
+        println!("{}", report);
+        std::process::exit(if report.is_valid() { 0 } else { 1 });
+    }
+
+    if options.export.is_some() {
+        if let Err(e) = cli::export_mesh(&options, &mesh, &lighting.material) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    // meshes can be any size and anywhere, so move them to where the
+    // camera is looking
+    if options.model.is_some() {
+        cli::fit_to_view(&mut mesh);
+    }
+    let vertex_data = mesh.interleave();
+    let index_data = mesh.indices;
+
+    if options.render {
+        if let Err(e) = cli::save_render(&options, &vertex_data, &index_data, &lighting) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if options.turntable.is_some() {
+        match cli::save_turntable(&options, &vertex_data, &index_data, &lighting) {
+            Ok(dir) => println!(
+                "wrote {} frames to {}",
+                options.turntable.unwrap_or(36),
+                dir.display()
+            ),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     let event_loop = EventLoop::new();
 
     #[cfg(not(feature = "gl"))]
@@ -99,43 +235,44 @@ fn main() {
         .unwrap(),
     );
 
-    // get teapot coordinates
-    let num_rows = 18;
-    let num_cols = 23;
-    let (vertex_data, normal_data, uv_data, index_data) =
-        teapot::create_vertices(num_rows, num_cols);
-    println!(
-        "num verts = {0}, num indices = {1}",
-        vertex_data.len(),
-        index_data.len()
+    // the same for the per-pixel lighting pipeline, from phong.vsh and phong.fsh
+    let phong_vs_module = device.create_shader_module(
+        &wgpu::read_spirv(
+            glsl_to_spirv::compile(
+                include_str!("phong.vsh"),
+                glsl_to_spirv::ShaderType::Vertex,
+            )
+            .unwrap(),
+        )
+        .unwrap(),
+    );
+    let phong_fs_module = device.create_shader_module(
+        &wgpu::read_spirv(
+            glsl_to_spirv::compile(
+                include_str!("phong.fsh"),
+                glsl_to_spirv::ShaderType::Fragment,
+            )
+            .unwrap(),
+        )
+        .unwrap(),
     );
 
-    // VERTEX buffer
-    let vertex_size = std::mem::size_of::<[f32; 4]>();
+    // VERTEX buffer, with the attributes of each vertex interleaved
     let vertex_buf =
         device.create_buffer_with_data(vertex_data.as_bytes(), wgpu::BufferUsage::VERTEX);
 
-    // NORMAL buffer
-    let normal_size = std::mem::size_of::<[f32; 3]>();
-    let normal_buf =
-        device.create_buffer_with_data(normal_data.as_bytes(), wgpu::BufferUsage::VERTEX);
-
-    // UV buffer
-    let uv_size = std::mem::size_of::<[f32; 2]>();
-    let uv_buf = device.create_buffer_with_data(uv_data.as_bytes(), wgpu::BufferUsage::VERTEX);
-
     // INDEX buffer
     let index_count = index_data.len();
     let index_buf = device.create_buffer_with_data(index_data.as_bytes(), wgpu::BufferUsage::INDEX);
 
-    // u_Transform
+    // Locals: u_Transform, u_Material and u_Light
     let aspect_ratio = 1.;
     let mut prev_width = 0;
     let mut prev_height = 0;
-    let mx_total = generate_matrix(aspect_ratio,0.0f32);
-    let mx_ref: &[f32; 16] = mx_total.as_ref();
+    let mx_total = teapot::generate_matrix(aspect_ratio,0.0f32);
+    let locals = teapot::Locals::new(&mx_total, &lighting);
     let uniform_buf = device.create_buffer_with_data(
-        mx_ref.as_bytes(),
+        locals.as_bytes(),
         wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
     );
 
@@ -146,7 +283,7 @@ fn main() {
     let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
         bindings: &[wgpu::BindGroupLayoutBinding {
             binding: 0,
-            visibility: wgpu::ShaderStage::VERTEX,
+            visibility: wgpu::ShaderStage::VERTEX | wgpu::ShaderStage::FRAGMENT,
             ty: wgpu::BindingType::UniformBuffer { dynamic: false },
         }],
     });
@@ -156,7 +293,7 @@ fn main() {
             binding: 0,
             resource: wgpu::BindingResource::Buffer {
                 buffer: &uniform_buf,
-                range: 0..64,
+                range: 0..teapot::Locals::SIZE as wgpu::BufferAddress,
             },
         }],
     });
@@ -164,76 +301,55 @@ fn main() {
         bind_group_layouts: &[&bind_group_layout],
     });
 
-    let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-        layout: &pipeline_layout,
-        vertex_stage: wgpu::ProgrammableStageDescriptor {
-            module: &vs_module,
-            entry_point: "main",
-        },
-        fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
-            module: &fs_module,
-            entry_point: "main",
-        }),
-        rasterization_state: Some(wgpu::RasterizationStateDescriptor {
-            front_face: wgpu::FrontFace::Ccw,
-            cull_mode: wgpu::CullMode::None,
-            depth_bias: 0,
-            depth_bias_slope_scale: 0.0,
-            depth_bias_clamp: 0.0,
-        }),
-        primitive_topology: wgpu::PrimitiveTopology::TriangleList,
-        color_states: &[wgpu::ColorStateDescriptor {
-            format: wgpu::TextureFormat::Bgra8UnormSrgb,
-            color_blend: wgpu::BlendDescriptor::REPLACE,
-            alpha_blend: wgpu::BlendDescriptor::REPLACE,
-            write_mask: wgpu::ColorWrite::ALL,
-        }],
-        depth_stencil_state: Some(wgpu::DepthStencilStateDescriptor {
-            format: wgpu::TextureFormat::Depth32Float,
-            depth_write_enabled: true,
-            depth_compare: wgpu::CompareFunction::Less,
-            stencil_front: wgpu::StencilStateFaceDescriptor::IGNORE,
-            stencil_back: wgpu::StencilStateFaceDescriptor::IGNORE,
-            stencil_read_mask: 0,
-            stencil_write_mask: 0,
-        }),
-        index_format: wgpu::IndexFormat::Uint32,
-        vertex_buffers: &[
-            // position [x,y,z,w] f32
-            wgpu::VertexBufferDescriptor {
-                stride: vertex_size as wgpu::BufferAddress,
-                step_mode: wgpu::InputStepMode::Vertex,
-                attributes: &[wgpu::VertexAttributeDescriptor {
-                    format: wgpu::VertexFormat::Float4,
-                    offset: 0,
-                    shader_location: 0,
-                }],
+    // The two pipelines differ only in their shaders: Gouraud shading,
+    // lighting each vertex, and Phong shading, lighting each pixel
+    let create_pipeline = |vs_module: &wgpu::ShaderModule, fs_module: &wgpu::ShaderModule| {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            layout: &pipeline_layout,
+            vertex_stage: wgpu::ProgrammableStageDescriptor {
+                module: vs_module,
+                entry_point: "main",
             },
-            // normal [x,y,z] f32
-            wgpu::VertexBufferDescriptor {
-                stride: normal_size as wgpu::BufferAddress,
+            fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+                module: fs_module,
+                entry_point: "main",
+            }),
+            rasterization_state: Some(wgpu::RasterizationStateDescriptor {
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: wgpu::CullMode::None,
+                depth_bias: 0,
+                depth_bias_slope_scale: 0.0,
+                depth_bias_clamp: 0.0,
+            }),
+            primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+            color_states: &[wgpu::ColorStateDescriptor {
+                format: wgpu::TextureFormat::Bgra8UnormSrgb,
+                color_blend: wgpu::BlendDescriptor::REPLACE,
+                alpha_blend: wgpu::BlendDescriptor::REPLACE,
+                write_mask: wgpu::ColorWrite::ALL,
+            }],
+            depth_stencil_state: Some(wgpu::DepthStencilStateDescriptor {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil_front: wgpu::StencilStateFaceDescriptor::IGNORE,
+                stencil_back: wgpu::StencilStateFaceDescriptor::IGNORE,
+                stencil_read_mask: 0,
+                stencil_write_mask: 0,
+            }),
+            index_format: wgpu::IndexFormat::Uint32,
+            vertex_buffers: &[wgpu::VertexBufferDescriptor {
+                stride: teapot::Vertex::STRIDE as wgpu::BufferAddress,
                 step_mode: wgpu::InputStepMode::Vertex,
-                attributes: &[wgpu::VertexAttributeDescriptor {
-                    format: wgpu::VertexFormat::Float3,
-                    offset: 0,
-                    shader_location: 1,
-                }],
-            },
-            // uv [u,v] f32
-            wgpu::VertexBufferDescriptor {
-                stride: uv_size as wgpu::BufferAddress,
-                step_mode: wgpu::InputStepMode::Vertex,
-                attributes: &[wgpu::VertexAttributeDescriptor {
-                    format: wgpu::VertexFormat::Float2,
-                    offset: 0,
-                    shader_location: 2,
-                }],
-            },
-        ],
-        sample_count: 1,
-        sample_mask: !0,
-        alpha_to_coverage_enabled: false,
-    });
+                attributes: &VERTEX_ATTRIBUTES,
+            }],
+            sample_count: 1,
+            sample_mask: !0,
+            alpha_to_coverage_enabled: false,
+        })
+    };
+    let render_pipeline = create_pipeline(&vs_module, &fs_module);
+    let phong_pipeline = create_pipeline(&phong_vs_module, &phong_fs_module);
 
     let mut sc_desc = wgpu::SwapChainDescriptor {
         usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
@@ -322,10 +438,10 @@ fn main() {
                     let duration = this_time.duration_since(prev_time);
                     if duration.unwrap().as_secs_f32() > 0.05 || sc_desc.width != prev_width || sc_desc.height != prev_height {
                         let aspect_ratio = sc_desc.width as f32 / sc_desc.height as f32;
-                        let mx_total = generate_matrix(aspect_ratio, this_time.duration_since(start_time).unwrap().as_secs_f32());
-                        let mx_ref: &[f32; 16] = mx_total.as_ref();
+                        let mx_total = teapot::generate_matrix(aspect_ratio, this_time.duration_since(start_time).unwrap().as_secs_f32());
+                        let locals = teapot::Locals::new(&mx_total, &lighting);
                         let uniform_buf = device.create_buffer_with_data(
-                            mx_ref.as_bytes(),
+                            locals.as_bytes(),
                             wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
                         );
                         bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -334,7 +450,7 @@ fn main() {
                                 binding: 0,
                                 resource: wgpu::BindingResource::Buffer {
                                     buffer: &uniform_buf,
-                                    range: 0..64,
+                                    range: 0..teapot::Locals::SIZE as wgpu::BufferAddress,
                                 },
                             }],
                         });
@@ -343,17 +459,51 @@ fn main() {
                         prev_time = this_time;
                     }
 
-                    rpass.set_pipeline(&render_pipeline);
+                    rpass.set_pipeline(match lighting.shading {
+                        teapot::Shading::Gouraud => &render_pipeline,
+                        teapot::Shading::Phong => &phong_pipeline,
+                    });
                     rpass.set_bind_group(0, &bind_group, &[]);
                     rpass.set_index_buffer(&index_buf, 0);
-                    rpass
-                        .set_vertex_buffers(0, &[(&vertex_buf, 0), (&normal_buf, 0), (&uv_buf, 0)]);
+                    rpass.set_vertex_buffers(0, &[(&vertex_buf, 0)]);
                     rpass.draw_indexed(0..index_count as u32, 0, 0..1);
 
                 }
 
                 queue.submit(&[encoder.finish()]);
             }
+            event::Event::WindowEvent {
+                event:
+                    event::WindowEvent::KeyboardInput {
+                        input:
+                            event::KeyboardInput {
+                                state: event::ElementState::Pressed,
+                                virtual_keycode: Some(key),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                // L goes back to the lighting file, or the defaults; the
+                // new uniforms are picked up with the next matrix
+                let changed = match key {
+                    event::VirtualKeyCode::L => match cli::read_lighting(&options) {
+                        Ok(l) => {
+                            lighting = l;
+                            true
+                        }
+                        Err(e) => {
+                            eprintln!("{}", e);
+                            false
+                        }
+                    },
+                    _ => adjust_lighting(&mut lighting, key),
+                };
+                if changed {
+                    print!("{}", lighting);
+                }
+            }
             event::Event::WindowEvent {
                 event: event::WindowEvent::CloseRequested,
                 ..
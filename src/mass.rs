@@ -0,0 +1,174 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use crate::mesh::Mesh;
+use crate::patch::PatchSet;
+use cgmath::*;
+
+/// Surface area, and the volume and mass distribution of the solid that a
+/// surface encloses, taking its density to be 1.
+///
+/// Everything but the area assumes the surface is closed, with outward
+/// normals. The teapot isn't quite: the handle and spout are open where
+/// they meet the body, and the lid sits on the rim without touching it,
+/// so its volume depends a little on where the origin is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MassProperties {
+    pub area: f64,
+    pub volume: f64,
+    /// Centre of mass. This isn't defined when the volume is zero.
+    pub centroid: Point3<f64>,
+    /// Inertia tensor about the centroid.
+    pub inertia: Matrix3<f64>,
+}
+
+// Integrals over the enclosed solid, turned into integrals over its surface
+// by the divergence theorem: each is the flux through the surface of a
+// vector field whose divergence is the integrand.
+struct Moments {
+    area: f64,
+    // ∫ 1
+    volume: f64,
+    // ∫ x, ∫ y, ∫ z
+    first: Vector3<f64>,
+    // ∫ x², ∫ y², ∫ z²
+    second: Vector3<f64>,
+    // ∫ yz, ∫ zx, ∫ xy
+    products: Vector3<f64>,
+}
+
+impl Moments {
+    fn new() -> Moments {
+        Moments {
+            area: 0.0,
+            volume: 0.0,
+            first: Vector3::zero(),
+            second: Vector3::zero(),
+            products: Vector3::zero(),
+        }
+    }
+
+    // Adds `weight` times the integrands at point `p`, where the surface has
+    // normal `n` scaled by the area element
+    fn add(&mut self, p: Vector3<f64>, n: Vector3<f64>, weight: f64) {
+        let (x, y, z) = (p.x, p.y, p.z);
+        self.area += weight * n.magnitude();
+        self.volume += weight * p.dot(n) / 3.0;
+        self.first += weight * Vector3::new(x * x * n.x, y * y * n.y, z * z * n.z) / 2.0;
+        self.second +=
+            weight * Vector3::new(x * x * x * n.x, y * y * y * n.y, z * z * z * n.z) / 3.0;
+        self.products +=
+            weight * Vector3::new(y * y * z * n.y, z * z * x * n.z, x * x * y * n.x) / 2.0;
+    }
+
+    fn properties(&self) -> MassProperties {
+        let m = self.volume;
+        let c = self.first / m;
+
+        // second moments about the centroid
+        let s = self.second - m * Vector3::new(c.x * c.x, c.y * c.y, c.z * c.z);
+        let p = self.products - m * Vector3::new(c.y * c.z, c.z * c.x, c.x * c.y);
+        MassProperties {
+            area: self.area,
+            volume: m,
+            centroid: Point3::from_vec(c),
+            inertia: Matrix3::new(
+                s.y + s.z,
+                -p.z,
+                -p.y,
+                -p.z,
+                s.z + s.x,
+                -p.x,
+                -p.y,
+                -p.x,
+                s.x + s.y,
+            ),
+        }
+    }
+}
+
+// 8 point Gauss-Legendre quadrature on [0, 1], as (parameter, weight). This
+// is exact for polynomials up to degree 15, which covers everything but the
+// area of a bicubic patch.
+const GAUSS_LEGENDRE: [(f64, f64); 8] = [
+    (0.019_855_071_751_231_856, 0.050_614_268_145_188_13),
+    (0.101_666_761_293_186_63, 0.111_190_517_226_687_24),
+    (0.237_233_795_041_835_5, 0.156_853_322_938_943_64),
+    (0.408_282_678_752_175_1, 0.181_341_891_689_181),
+    (0.591_717_321_247_825, 0.181_341_891_689_181),
+    (0.762_766_204_958_164_5, 0.156_853_322_938_943_64),
+    (0.898_333_238_706_813_4, 0.111_190_517_226_687_24),
+    (0.980_144_928_248_768_1, 0.050_614_268_145_188_13),
+];
+
+// Each patch is split into this many cells along u and v for the area
+const CELLS: usize = 8;
+
+impl PatchSet {
+    /// The area, volume, centroid and inertia of the surface, from the
+    /// patches themselves rather than a tessellation.
+    ///
+    /// The volume, centroid and inertia are polynomials in the control
+    /// points, which quadrature gives exactly. The area isn't, so it's
+    /// accurate to about 9 significant figures.
+    ///
+    /// ```
+    /// let patches = teapot::control_points();
+    /// let exact = patches.mass_properties();
+    /// let mesh = teapot::create_vertices(64, 64).mass_properties();
+    /// assert!((mesh.area - exact.area).abs() < 1e-3 * exact.area);
+    /// assert!((mesh.volume - exact.volume).abs() < 1e-3 * exact.volume);
+    /// ```
+    pub fn mass_properties(&self) -> MassProperties {
+        let mut moments = Moments::new();
+        let size = 1.0 / CELLS as f64;
+        for patch in self.iter() {
+            for i in 0..CELLS {
+                for j in 0..CELLS {
+                    for &(s, ws) in GAUSS_LEGENDRE.iter() {
+                        for &(t, wt) in GAUSS_LEGENDRE.iter() {
+                            let u = (i as f64 + s) * size;
+                            let v = (j as f64 + t) * size;
+                            let sp = patch.evaluate(u, v);
+                            let n = sp.du.cross(sp.dv);
+                            moments.add(sp.position.to_vec(), n, ws * wt * size * size);
+                        }
+                    }
+                }
+            }
+        }
+        moments.properties()
+    }
+}
+
+impl Mesh {
+    /// The area, volume, centroid and inertia of the triangles, which tend
+    /// to those of the patches they came from as the tessellation gets
+    /// finer.
+    ///
+    /// These are exact for the mesh, other than rounding.
+    pub fn mass_properties(&self) -> MassProperties {
+        // quadrature on a triangle that's exact for cubics, as (barycentric
+        // coordinates, weight)
+        let third = 1.0 / 3.0;
+        let rule = [
+            ([third, third, third], -27.0 / 48.0),
+            ([0.6, 0.2, 0.2], 25.0 / 48.0),
+            ([0.2, 0.6, 0.2], 25.0 / 48.0),
+            ([0.2, 0.2, 0.6], 25.0 / 48.0),
+        ];
+
+        let mut moments = Moments::new();
+        for tri in self.indices.chunks(3) {
+            let p = |k: usize| {
+                let v = self.vertices[tri[k] as usize];
+                Vector3::new(v[0], v[1], v[2]).cast::<f64>().unwrap()
+            };
+            let (a, b, c) = (p(0), p(1), p(2));
+            let n = (b - a).cross(c - a) / 2.0;
+            for &(w, weight) in rule.iter() {
+                moments.add(a * w[0] + b * w[1] + c * w[2], n, weight);
+            }
+        }
+        moments.properties()
+    }
+}
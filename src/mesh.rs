@@ -0,0 +1,150 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use std::mem::size_of;
+use std::ops::Range;
+use zerocopy::AsBytes;
+
+/// One vertex of a [`Mesh`], with all its attributes together, for
+/// uploading to the GPU as a single interleaved buffer.
+#[repr(C)]
+#[derive(AsBytes, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vertex {
+    /// Homogeneous position `[x, y, z, 1]`.
+    pub pos: [f32; 4],
+    /// Unit normal.
+    pub normal: [f32; 3],
+    /// Patch parameters `[u, v]`.
+    pub uv: [f32; 2],
+    /// Unit tangent along `u`, with the handedness in `w`.
+    pub tangent: [f32; 4],
+}
+
+impl Vertex {
+    /// Byte offset of `pos` in a vertex.
+    pub const POS_OFFSET: usize = 0;
+    /// Byte offset of `normal` in a vertex.
+    pub const NORMAL_OFFSET: usize = Vertex::POS_OFFSET + size_of::<[f32; 4]>();
+    /// Byte offset of `uv` in a vertex.
+    pub const UV_OFFSET: usize = Vertex::NORMAL_OFFSET + size_of::<[f32; 3]>();
+    /// Byte offset of `tangent` in a vertex.
+    pub const TANGENT_OFFSET: usize = Vertex::UV_OFFSET + size_of::<[f32; 2]>();
+    /// Bytes from one vertex to the next in an interleaved buffer.
+    pub const STRIDE: usize = size_of::<Vertex>();
+}
+
+/// An indexed triangle list with the per-vertex attributes the viewer
+/// uploads to the GPU. The vertex arrays are parallel: entry `i` of
+/// `vertices`, `normals`, `uvs` and `tangents`, if there are any, all
+/// describe vertex `i`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Mesh {
+    /// Homogeneous positions `[x, y, z, 1]`.
+    pub vertices: Vec<[f32; 4]>,
+    /// Unit normals.
+    pub normals: Vec<[f32; 3]>,
+    /// Patch parameters `[u, v]`, each in `[0, 1]`.
+    pub uvs: Vec<[f32; 2]>,
+    /// Unit tangents along `u`, perpendicular to the normals, with the
+    /// handedness of the tangent frame in `w` as in
+    /// [`SurfacePoint::tangent`](crate::SurfacePoint::tangent). Empty if
+    /// the mesh has none, in which case
+    /// [`generate_tangents`](Mesh::generate_tangents) can make them.
+    pub tangents: Vec<[f32; 4]>,
+    /// Three indices per triangle, counter-clockwise.
+    pub indices: Vec<u32>,
+    /// The range of `indices` holding each patch's triangles, for meshes
+    /// made from patches. Empty otherwise.
+    pub patch_ranges: Vec<Range<usize>>,
+}
+
+impl Mesh {
+    /// Creates an empty mesh.
+    pub fn new() -> Mesh {
+        Mesh::default()
+    }
+
+    /// Creates an empty mesh with room for `num_vertices` vertices and
+    /// `num_indices` indices.
+    pub fn with_capacity(num_vertices: usize, num_indices: usize) -> Mesh {
+        Mesh {
+            vertices: Vec::with_capacity(num_vertices),
+            normals: Vec::with_capacity(num_vertices),
+            uvs: Vec::with_capacity(num_vertices),
+            tangents: Vec::with_capacity(num_vertices),
+            indices: Vec::with_capacity(num_indices),
+            patch_ranges: Vec::new(),
+        }
+    }
+
+    /// Number of vertices.
+    pub fn num_vertices(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Number of triangles.
+    pub fn num_triangles(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// The indices of patch `i`'s triangles.
+    pub fn patch_indices(&self, i: usize) -> &[u32] {
+        &self.indices[self.patch_ranges[i].clone()]
+    }
+
+    /// Whether there is a tangent for every vertex.
+    pub fn has_tangents(&self) -> bool {
+        self.tangents.len() == self.vertices.len()
+    }
+
+    /// The vertices with their attributes interleaved, to upload as one
+    /// buffer. Without tangents, those are left zero.
+    ///
+    /// ```
+    /// use zerocopy::AsBytes;
+    ///
+    /// let mesh = teapot::create_vertices(4, 4);
+    /// let vertices = mesh.interleave();
+    /// assert_eq!(vertices[5].normal, mesh.normals[5]);
+    /// assert_eq!(vertices.as_bytes().len(), mesh.num_vertices() * teapot::Vertex::STRIDE);
+    /// ```
+    pub fn interleave(&self) -> Vec<Vertex> {
+        self.vertices
+            .iter()
+            .zip(self.normals.iter())
+            .zip(self.uvs.iter())
+            .enumerate()
+            .map(|(i, ((&pos, &normal), &uv))| Vertex {
+                pos,
+                normal,
+                uv,
+                tangent: self.tangents.get(i).cloned().unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    /// Appends another mesh, rebasing its indices to follow the vertices
+    /// already in this one. The result only has tangents if both meshes do.
+    pub fn append(&mut self, other: &Mesh) {
+        if self.has_tangents() && other.has_tangents() {
+            self.tangents.extend_from_slice(&other.tangents);
+        } else {
+            self.tangents.clear();
+        }
+
+        let base = self.vertices.len() as u32;
+        let offset = self.indices.len();
+        self.patch_ranges.extend(
+            other
+                .patch_ranges
+                .iter()
+                .map(|r| r.start + offset..r.end + offset),
+        );
+
+        self.vertices.extend_from_slice(&other.vertices);
+        self.normals.extend_from_slice(&other.normals);
+        self.uvs.extend_from_slice(&other.uvs);
+
+        // indices have to be rebased
+        self.indices.extend(other.indices.iter().map(|i| base + i));
+    }
+}
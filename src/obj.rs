@@ -0,0 +1,229 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use crate::loader::{parse_error, parse_field, LoadError};
+use crate::mesh::Mesh;
+use cgmath::*;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Writes `mesh` as a Wavefront OBJ file, with a position, texture
+/// coordinate and normal for every vertex and a `g patchN` group for each
+/// patch in [`Mesh::patch_ranges`].
+///
+/// Vertices are written in order and faces refer to the same index for all
+/// three attributes, so reading the file back gives the same vertices and
+/// triangles. Coordinates are written with the fewest digits that read back
+/// as the same `f32`.
+///
+/// ```
+/// let mesh = teapot::create_vertices(4, 4);
+/// let mut obj = Vec::new();
+/// teapot::write_obj(&mesh, &mut obj).unwrap();
+/// let text = String::from_utf8(obj).unwrap();
+/// assert_eq!(text.lines().filter(|l| l.starts_with("v ")).count(), 32 * 16 - 8 * 3);
+/// ```
+pub fn write_obj<W: Write>(mesh: &Mesh, out: W) -> io::Result<()> {
+    let mut out = out;
+    for v in mesh.vertices.iter() {
+        writeln!(out, "v {} {} {}", v[0], v[1], v[2])?;
+    }
+    for t in mesh.uvs.iter() {
+        writeln!(out, "vt {} {}", t[0], t[1])?;
+    }
+    for n in mesh.normals.iter() {
+        writeln!(out, "vn {} {} {}", n[0], n[1], n[2])?;
+    }
+
+    let write_faces = |out: &mut W, indices: &[u32]| -> io::Result<()> {
+        for tri in indices.chunks(3) {
+            // OBJ indices are 1-based
+            let (a, b, c) = (tri[0] + 1, tri[1] + 1, tri[2] + 1);
+            writeln!(out, "f {0}/{0}/{0} {1}/{1}/{1} {2}/{2}/{2}", a, b, c)?;
+        }
+        Ok(())
+    };
+    if mesh.patch_ranges.is_empty() {
+        write_faces(&mut out, &mesh.indices)?;
+    }
+    for (i, range) in mesh.patch_ranges.iter().enumerate() {
+        writeln!(out, "g patch{}", i)?;
+        write_faces(&mut out, &mesh.indices[range.clone()])?;
+    }
+    out.flush()
+}
+
+/// Writes `mesh` to an OBJ file at `path`, as [`write_obj`] does.
+pub fn save_obj<P: AsRef<Path>>(mesh: &Mesh, path: P) -> io::Result<()> {
+    write_obj(mesh, BufWriter::new(File::create(path)?))
+}
+
+/// Reads a Wavefront OBJ file with [`parse_obj`].
+pub fn load_obj<P: AsRef<Path>>(path: P) -> Result<Mesh, LoadError> {
+    parse_obj(&std::fs::read_to_string(path)?)
+}
+
+/// Parses the triangles of a Wavefront OBJ file into a [`Mesh`].
+///
+/// Faces with more than three corners are split into fans of triangles.
+/// Each distinct combination of position, texture coordinate and normal
+/// that a face uses becomes one vertex, in the order they're first used.
+/// Corners without a normal get the angle-weighted average of the normals
+/// of the faces around their position, and corners without a texture coordinate get `(0, 0)`.
+/// Vertex colours, written as `v x y z r g b` by MeshLab and others, are
+/// ignored, as are groups, materials, lines and everything else.
+///
+/// ```
+/// let text = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n";
+/// let mesh = teapot::parse_obj(text).unwrap();
+/// assert_eq!(mesh.num_vertices(), 4);
+/// assert_eq!(mesh.num_triangles(), 2);
+/// assert_eq!(mesh.normals[0], [0.0, 0.0, 1.0]);
+/// ```
+pub fn parse_obj(text: &str) -> Result<Mesh, LoadError> {
+    let mut positions: Vec<[f32; 4]> = Vec::new();
+    let mut uvs: Vec<[f32; 2]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+
+    // (position, uv, normal) of each vertex, and their indices
+    let mut corners: Vec<(usize, Option<usize>, Option<usize>)> = Vec::new();
+    let mut vertex_index: HashMap<(usize, Option<usize>, Option<usize>), u32> = HashMap::new();
+    let mut indices: Vec<u32> = Vec::new();
+
+    for (i, l) in text.lines().enumerate() {
+        let line = i + 1;
+        let mut fields = l.split_whitespace();
+        let keyword = match fields.next() {
+            Some(k) => k,
+            None => continue,
+        };
+        let fields: Vec<&str> = fields.collect();
+        let floats = |min: usize, max: usize| -> Result<Vec<f32>, LoadError> {
+            if fields.len() < min || fields.len() > max {
+                return Err(parse_error(
+                    line,
+                    format!("expected {} to {} values, found {}", min, max, fields.len()),
+                ));
+            }
+            fields
+                .iter()
+                .map(|f| parse_field(line, f, "coordinate"))
+                .collect()
+        };
+
+        match keyword {
+            "v" => {
+                // x y z, x y z w, or x y z and a colour
+                let p = floats(3, 7)?;
+                let w = if p.len() == 4 { p[3] } else { 1.0 };
+                positions.push([p[0], p[1], p[2], w]);
+            }
+            "vt" => {
+                let t = floats(1, 3)?;
+                uvs.push([t[0], t.get(1).cloned().unwrap_or(0.0)]);
+            }
+            "vn" => {
+                let n = floats(3, 3)?;
+                normals.push([n[0], n[1], n[2]]);
+            }
+            "f" => {
+                if fields.len() < 3 {
+                    return Err(parse_error(
+                        line,
+                        format!("expected at least 3 corners, found {}", fields.len()),
+                    ));
+                }
+                let mut face = Vec::with_capacity(fields.len());
+                for f in fields.iter() {
+                    let mut refs = f.split('/');
+                    let mut index =
+                        |count: usize, what: &str| -> Result<Option<usize>, LoadError> {
+                            match refs.next() {
+                                None | Some("") => Ok(None),
+                                Some(r) => resolve_index(line, r, count, what).map(Some),
+                            }
+                        };
+                    let p = index(positions.len(), "vertex")?.ok_or_else(|| {
+                        parse_error(line, format!("corner '{}' has no vertex", f))
+                    })?;
+                    let key = (
+                        p,
+                        index(uvs.len(), "texture")?,
+                        index(normals.len(), "normal")?,
+                    );
+                    let next = corners.len() as u32;
+                    let v = *vertex_index.entry(key).or_insert(next);
+                    if v == next {
+                        corners.push(key);
+                    }
+                    face.push(v);
+                }
+                for k in 1..face.len() - 1 {
+                    indices.extend_from_slice(&[face[0], face[k], face[k + 1]]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // normals for corners that don't have one, shared by every vertex at the
+    // same position so that seams in the texture coordinates stay smooth.
+    // Each face counts in proportion to its angle at the corner, which
+    // doesn't depend on how polygons were split into triangles.
+    let mut vertex_normals: HashMap<usize, Vector3<f32>> = HashMap::new();
+    if corners.iter().any(|c| c.2.is_none()) {
+        for tri in indices.chunks(3) {
+            let p = |k: usize| {
+                let v = positions[corners[tri[k] as usize].0];
+                Vector3::new(v[0], v[1], v[2])
+            };
+            let n = (p(1) - p(0)).cross(p(2) - p(0));
+            if n.magnitude2() == 0.0 {
+                continue;
+            }
+            let n = n.normalize();
+            for k in 0..3 {
+                let a = p((k + 1) % 3) - p(k);
+                let b = p((k + 2) % 3) - p(k);
+                let angle = a.angle(b).0;
+                if angle.is_finite() {
+                    *vertex_normals
+                        .entry(corners[tri[k] as usize].0)
+                        .or_insert_with(Vector3::zero) += angle * n;
+                }
+            }
+        }
+    }
+
+    let mut mesh = Mesh::with_capacity(corners.len(), indices.len());
+    for &(p, t, n) in corners.iter() {
+        mesh.vertices.push(positions[p]);
+        mesh.uvs.push(t.map(|t| uvs[t]).unwrap_or([0.0, 0.0]));
+        let normal = match n {
+            Some(n) => normals[n],
+            None => match vertex_normals.get(&p) {
+                Some(sum) if sum.magnitude2() > 0.0 => sum.normalize().into(),
+                _ => [0.0, 0.0, 1.0],
+            },
+        };
+        mesh.normals.push(normal);
+    }
+    mesh.indices = indices;
+    mesh.generate_tangents();
+    Ok(mesh)
+}
+
+// Turns a 1-based or negative (counting back from the end) OBJ reference to
+// one of `count` elements into a 0-based index
+fn resolve_index(line: usize, field: &str, count: usize, what: &str) -> Result<usize, LoadError> {
+    let i: i64 = parse_field(line, field, &format!("{} index", what))?;
+    let index = if i < 0 { count as i64 + i } else { i - 1 };
+    if index < 0 || index >= count as i64 {
+        return Err(parse_error(
+            line,
+            format!("{} index {} out of range", what, i),
+        ));
+    }
+    Ok(index as usize)
+}
@@ -0,0 +1,214 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use cgmath::*;
+use std::ops::Index;
+
+/// An axis aligned bounding box.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingBox {
+    pub min: Point3<f32>,
+    pub max: Point3<f32>,
+}
+
+impl BoundingBox {
+    /// The smallest box containing all of `points`, or `None` if there
+    /// aren't any.
+    pub fn from_points<'a, I>(points: I) -> Option<BoundingBox>
+    where
+        I: IntoIterator<Item = &'a Point3<f32>>,
+    {
+        let mut iter = points.into_iter();
+        let first = *iter.next()?;
+        let mut bbox = BoundingBox {
+            min: first,
+            max: first,
+        };
+        for p in iter {
+            bbox.add_point(*p);
+        }
+        Some(bbox)
+    }
+
+    /// Grows the box to contain `p`.
+    pub fn add_point(&mut self, p: Point3<f32>) {
+        self.min = Point3::new(
+            self.min.x.min(p.x),
+            self.min.y.min(p.y),
+            self.min.z.min(p.z),
+        );
+        self.max = Point3::new(
+            self.max.x.max(p.x),
+            self.max.y.max(p.y),
+            self.max.z.max(p.z),
+        );
+    }
+
+    /// The smallest box containing both boxes.
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        let mut bbox = *self;
+        bbox.add_point(other.min);
+        bbox.add_point(other.max);
+        bbox
+    }
+
+    /// The point halfway between the corners.
+    pub fn center(&self) -> Point3<f32> {
+        self.min.midpoint(self.max)
+    }
+
+    /// The extent along each axis, from `min` to `max`.
+    pub fn size(&self) -> Vector3<f32> {
+        self.max - self.min
+    }
+}
+
+/// One of the four boundary curves of a [`BezierPatch`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// `v = 0`, the first row of control points.
+    Bottom,
+    /// `u = 1`, the last column.
+    Right,
+    /// `v = 1`, the last row.
+    Top,
+    /// `u = 0`, the first column.
+    Left,
+}
+
+impl Side {
+    pub const ALL: [Side; 4] = [Side::Bottom, Side::Right, Side::Top, Side::Left];
+}
+
+/// A bicubic Bezier patch, defined by a 4 X 4 grid of control points
+/// indexed `[row][column]`. Rows run along `v` and columns along `u`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BezierPatch {
+    points: [[Point3<f32>; 4]; 4],
+}
+
+impl BezierPatch {
+    pub fn new(points: [[Point3<f32>; 4]; 4]) -> BezierPatch {
+        BezierPatch { points }
+    }
+
+    /// The control points, indexed `[row][column]`.
+    pub fn points(&self) -> &[[Point3<f32>; 4]; 4] {
+        &self.points
+    }
+
+    /// Iterates over all 16 control points in row order.
+    pub fn iter(&self) -> impl Iterator<Item = &Point3<f32>> {
+        self.points.iter().flat_map(|row| row.iter())
+    }
+
+    /// The control points of the cubic Bezier curve along `side`, in order
+    /// of increasing `u` or `v`.
+    pub fn edge(&self, side: Side) -> [Point3<f32>; 4] {
+        let p = &self.points;
+        match side {
+            Side::Bottom => p[0],
+            Side::Top => p[3],
+            Side::Left => [p[0][0], p[1][0], p[2][0], p[3][0]],
+            Side::Right => [p[0][3], p[1][3], p[2][3], p[3][3]],
+        }
+    }
+
+    /// Bounds the control points, and therefore the surface, which lies
+    /// inside their convex hull.
+    pub fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::from_points(self.iter()).unwrap()
+    }
+
+    /// Applies `t` to every control point. Bezier patches are affine
+    /// invariant, so this transforms the surface too.
+    pub fn transform<T: Transform<Point3<f32>>>(&self, t: &T) -> BezierPatch {
+        let mut points = self.points;
+        for row in points.iter_mut() {
+            for p in row.iter_mut() {
+                *p = t.transform_point(*p);
+            }
+        }
+        BezierPatch { points }
+    }
+}
+
+impl Index<(usize, usize)> for BezierPatch {
+    type Output = Point3<f32>;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Point3<f32> {
+        &self.points[row][col]
+    }
+}
+
+/// A model made of Bezier patches, such as the teapot.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PatchSet {
+    patches: Vec<BezierPatch>,
+}
+
+impl PatchSet {
+    pub fn new(patches: Vec<BezierPatch>) -> PatchSet {
+        PatchSet { patches }
+    }
+
+    pub fn len(&self) -> usize {
+        self.patches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patches.is_empty()
+    }
+
+    pub fn push(&mut self, patch: BezierPatch) {
+        self.patches.push(patch);
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, BezierPatch> {
+        self.patches.iter()
+    }
+
+    pub fn as_slice(&self) -> &[BezierPatch] {
+        &self.patches
+    }
+
+    /// Bounds all of the patches, or `None` if the set is empty.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        self.patches
+            .iter()
+            .map(|p| p.bounding_box())
+            .fold(None, |acc, b| match acc {
+                Some(a) => Some(b.union(&a)),
+                None => Some(b),
+            })
+    }
+
+    /// Applies `t` to every patch.
+    pub fn transform<T: Transform<Point3<f32>>>(&self, t: &T) -> PatchSet {
+        self.patches.iter().map(|p| p.transform(t)).collect()
+    }
+}
+
+impl Index<usize> for PatchSet {
+    type Output = BezierPatch;
+
+    fn index(&self, i: usize) -> &BezierPatch {
+        &self.patches[i]
+    }
+}
+
+impl<'a> IntoIterator for &'a PatchSet {
+    type Item = &'a BezierPatch;
+    type IntoIter = std::slice::Iter<'a, BezierPatch>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.patches.iter()
+    }
+}
+
+impl std::iter::FromIterator<BezierPatch> for PatchSet {
+    fn from_iter<I: IntoIterator<Item = BezierPatch>>(iter: I) -> PatchSet {
+        PatchSet {
+            patches: iter.into_iter().collect(),
+        }
+    }
+}
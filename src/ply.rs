@@ -0,0 +1,83 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use crate::mesh::Mesh;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Which per-vertex attributes [`write_ply`] writes besides positions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlyOptions {
+    /// Write `nx ny nz` for each vertex.
+    pub normals: bool,
+    /// Write the patch parameters as texture coordinates `s t`.
+    pub uvs: bool,
+}
+
+impl Default for PlyOptions {
+    fn default() -> PlyOptions {
+        PlyOptions {
+            normals: true,
+            uvs: true,
+        }
+    }
+}
+
+/// Writes `mesh` as an ASCII PLY file: a vertex element with `x y z` and
+/// whichever of the normals and uvs `options` asks for, and a face element
+/// with three vertex indices per triangle.
+///
+/// ```
+/// let mesh = teapot::create_vertices(4, 4);
+/// let options = teapot::PlyOptions { normals: false, uvs: false };
+/// let mut ply = Vec::new();
+/// teapot::write_ply(&mesh, &options, &mut ply).unwrap();
+/// let text = String::from_utf8(ply).unwrap();
+/// assert!(text.contains("element vertex 488\n"));
+/// assert!(!text.contains("property float nx"));
+/// ```
+pub fn write_ply<W: Write>(mesh: &Mesh, options: &PlyOptions, out: W) -> io::Result<()> {
+    let mut out = out;
+    writeln!(out, "ply")?;
+    writeln!(out, "format ascii 1.0")?;
+    writeln!(out, "comment teapot")?;
+    writeln!(out, "element vertex {}", mesh.num_vertices())?;
+    for p in ["x", "y", "z"].iter() {
+        writeln!(out, "property float {}", p)?;
+    }
+    if options.normals {
+        for p in ["nx", "ny", "nz"].iter() {
+            writeln!(out, "property float {}", p)?;
+        }
+    }
+    if options.uvs {
+        for p in ["s", "t"].iter() {
+            writeln!(out, "property float {}", p)?;
+        }
+    }
+    writeln!(out, "element face {}", mesh.num_triangles())?;
+    writeln!(out, "property list uchar uint vertex_indices")?;
+    writeln!(out, "end_header")?;
+
+    for (i, v) in mesh.vertices.iter().enumerate() {
+        write!(out, "{} {} {}", v[0], v[1], v[2])?;
+        if options.normals {
+            let n = mesh.normals[i];
+            write!(out, " {} {} {}", n[0], n[1], n[2])?;
+        }
+        if options.uvs {
+            let t = mesh.uvs[i];
+            write!(out, " {} {}", t[0], t[1])?;
+        }
+        writeln!(out)?;
+    }
+    for tri in mesh.indices.chunks(3) {
+        writeln!(out, "3 {} {} {}", tri[0], tri[1], tri[2])?;
+    }
+    out.flush()
+}
+
+/// Writes `mesh` to an ASCII PLY file at `path`, as [`write_ply`] does.
+pub fn save_ply<P: AsRef<Path>>(mesh: &Mesh, options: &PlyOptions, path: P) -> io::Result<()> {
+    write_ply(mesh, options, BufWriter::new(File::create(path)?))
+}
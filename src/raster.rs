@@ -0,0 +1,319 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use crate::camera::{generate_matrix, ORBIT_PERIOD};
+use crate::image::Image;
+use crate::mesh::Vertex;
+use crate::shading::{blinn_phong, eye_position, eye_vector, view_vector, Lighting, Shading};
+use cgmath::*;
+
+/// A colour and depth buffer that triangles are drawn into on the CPU, the
+/// way the viewer's pipeline draws them on the GPU.
+///
+/// The lighting is the Gouraud shading in `shader.vsh` and `shader.fsh`,
+/// or the per-pixel shading in `phong.vsh` and `phong.fsh`, with the
+/// default [`Lighting`] unless it's [set](RenderTarget::set_lighting).
+/// The depth test keeps the nearest fragment and both sides of each
+/// triangle are drawn. This needs no window or GPU, so it can render on
+/// build machines and for golden-image tests.
+///
+/// ```
+/// let mesh = teapot::create_vertices(8, 8);
+/// let transform = teapot::generate_matrix(4.0 / 3.0, 0.0);
+/// let mut target = teapot::RenderTarget::new(64, 48);
+/// target.draw_indexed(&mesh.interleave(), &mesh.indices, &transform);
+/// let image = target.to_image();
+/// assert_eq!(image.pixel(0, 0), [0, 0, 0]);
+/// assert_ne!(image.pixel(32, 24), [0, 0, 0]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct RenderTarget {
+    width: u32,
+    height: u32,
+    // linear colour, before the sRGB encoding the swap chain does
+    color: Vec<Vector3<f32>>,
+    depth: Vec<f32>,
+    lighting: Lighting,
+}
+
+// A vertex after the vertex shader, with what either pipeline passes on
+#[derive(Clone, Copy)]
+struct ClipVertex {
+    position: Vector4<f32>,
+    diffuse: Vector3<f32>,
+    specular: Vector3<f32>,
+    world: Vector3<f32>,
+    normal: Vector3<f32>,
+}
+
+impl ClipVertex {
+    fn lerp(&self, other: &ClipVertex, t: f32) -> ClipVertex {
+        ClipVertex {
+            position: self.position.lerp(other.position, t),
+            diffuse: self.diffuse.lerp(other.diffuse, t),
+            specular: self.specular.lerp(other.specular, t),
+            world: self.world.lerp(other.world, t),
+            normal: self.normal.lerp(other.normal, t),
+        }
+    }
+}
+
+impl RenderTarget {
+    /// A target cleared to black, at the far plane.
+    pub fn new(width: u32, height: u32) -> RenderTarget {
+        let n = width as usize * height as usize;
+        RenderTarget {
+            width,
+            height,
+            color: vec![Vector3::zero(); n],
+            depth: vec![1.0; n],
+            lighting: Lighting::default(),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn lighting(&self) -> &Lighting {
+        &self.lighting
+    }
+
+    /// Sets the material, light and shading for the triangles drawn after
+    /// this.
+    pub fn set_lighting(&mut self, lighting: Lighting) {
+        self.lighting = lighting;
+    }
+
+    /// Clears the colour to black and the depth to the far plane, as the
+    /// viewer's render pass does.
+    pub fn clear(&mut self) {
+        for c in self.color.iter_mut() {
+            *c = Vector3::zero();
+        }
+        for d in self.depth.iter_mut() {
+            *d = 1.0;
+        }
+    }
+
+    /// Depth of the nearest fragment drawn at pixel `(x, y)`, from 0 at the
+    /// near plane to 1 at the far plane or where nothing has been drawn.
+    pub fn depth(&self, x: u32, y: u32) -> f32 {
+        self.depth[self.index(x, y)]
+    }
+
+    /// Draws the triangles in `indices` from the viewer's vertex and index
+    /// buffers, with `transform` taking them to clip space as the
+    /// `u_Transform` uniform does.
+    pub fn draw_indexed(&mut self, vertices: &[Vertex], indices: &[u32], transform: &Matrix4<f32>) {
+        // the vertex shader, once per vertex
+        let view = eye_vector(transform);
+        let eye = eye_position(transform);
+        let shaded: Vec<ClipVertex> = vertices
+            .iter()
+            .map(|v| {
+                let normal = Vector3::from(v.normal);
+                let (diffuse, specular) = match self.lighting.shading {
+                    Shading::Gouraud => blinn_phong(normal, view, &self.lighting),
+                    Shading::Phong => (Vector3::zero(), Vector3::zero()),
+                };
+                let pos = Vector4::from(v.pos);
+                ClipVertex {
+                    position: transform * pos,
+                    diffuse,
+                    specular,
+                    world: pos.truncate() / pos.w,
+                    normal,
+                }
+            })
+            .collect();
+
+        for tri in indices.chunks(3) {
+            if tri.len() < 3 {
+                break;
+            }
+            let corners = [
+                shaded[tri[0] as usize],
+                shaded[tri[1] as usize],
+                shaded[tri[2] as usize],
+            ];
+            let polygon = clip(&corners);
+            for k in 1..polygon.len().saturating_sub(1) {
+                self.fill(&polygon[0], &polygon[k], &polygon[k + 1], eye);
+            }
+        }
+    }
+
+    /// The colour buffer as an 8 bit image, encoded as sRGB like the
+    /// viewer's swap chain.
+    pub fn to_image(&self) -> Image {
+        let mut image = Image::new(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let c = self.color[self.index(x, y)];
+                image.set_pixel(x, y, [srgb(c.x), srgb(c.y), srgb(c.z)]);
+            }
+        }
+        image
+    }
+
+    // In usize, as a target can have more pixels than fit in a u32
+    fn index(&self, x: u32, y: u32) -> usize {
+        y as usize * self.width as usize + x as usize
+    }
+
+    // Rasterizes a triangle that's in front of the camera at `eye`
+    fn fill(&mut self, a: &ClipVertex, b: &ClipVertex, c: &ClipVertex, eye: Vector4<f32>) {
+        // to pixels, with y already pointing down the screen
+        let (w, h) = (self.width as f32, self.height as f32);
+        let screen = |v: &ClipVertex| {
+            let p = v.position;
+            Vector3::new(
+                (p.x / p.w + 1.0) * 0.5 * w,
+                (p.y / p.w + 1.0) * 0.5 * h,
+                p.z / p.w,
+            )
+        };
+        let (sa, sb, sc) = (screen(a), screen(b), screen(c));
+        let area = edge(sa, sb, sc);
+        if area == 0.0 || !area.is_finite() {
+            return;
+        }
+
+        let x0 = sa.x.min(sb.x).min(sc.x).max(0.0).floor() as u32;
+        let y0 = sa.y.min(sb.y).min(sc.y).max(0.0).floor() as u32;
+        let x1 = (sa.x.max(sb.x).max(sc.x).ceil().max(0.0) as u32).min(self.width);
+        let y1 = (sa.y.max(sb.y).max(sc.y).ceil().max(0.0) as u32).min(self.height);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                // sample at the pixel centre
+                let p = Vector3::new(x as f32 + 0.5, y as f32 + 0.5, 0.0);
+                let (wa, wb, wc) = (
+                    edge(sb, sc, p) / area,
+                    edge(sc, sa, p) / area,
+                    edge(sa, sb, p) / area,
+                );
+                if wa < 0.0 || wb < 0.0 || wc < 0.0 {
+                    continue;
+                }
+
+                // depth is linear on the screen, the colours are linear in
+                // the world, so they need the perspective divide
+                let z = wa * sa.z + wb * sb.z + wc * sc.z;
+                let i = self.index(x, y);
+                if z < 0.0 || z >= self.depth[i] {
+                    continue;
+                }
+                let (pa, pb, pc) = (wa / a.position.w, wb / b.position.w, wc / c.position.w);
+                let sum = pa + pb + pc;
+                let (pa, pb, pc) = (pa / sum, pb / sum, pc / sum);
+                let (diffuse, specular) = match self.lighting.shading {
+                    Shading::Gouraud => (
+                        a.diffuse * pa + b.diffuse * pb + c.diffuse * pc,
+                        a.specular * pa + b.specular * pb + c.specular * pc,
+                    ),
+                    Shading::Phong => {
+                        let world = a.world * pa + b.world * pb + c.world * pc;
+                        let normal = a.normal * pa + b.normal * pb + c.normal * pc;
+                        let view = view_vector(eye, world);
+                        blinn_phong(normal.normalize(), view, &self.lighting)
+                    }
+                };
+
+                // the fragment shader
+                let color = diffuse + specular;
+                self.depth[i] = z;
+                self.color[i] = Vector3::new(color.x.min(1.0), color.y.min(1.0), color.z.min(1.0));
+            }
+        }
+    }
+}
+
+/// Renders the viewer's vertex and index buffers on the CPU, to a new
+/// `width` X `height` image with `transform` from
+/// [`generate_matrix`](crate::generate_matrix) or the like.
+pub fn render(
+    vertices: &[Vertex],
+    indices: &[u32],
+    transform: &Matrix4<f32>,
+    width: u32,
+    height: u32,
+) -> Image {
+    let mut target = RenderTarget::new(width, height);
+    target.draw_indexed(vertices, indices, transform);
+    target.to_image()
+}
+
+/// Renders `frames` images of one orbit of the viewer's camera, at evenly
+/// spaced times from 0, for turntable animations, shaded with `lighting`.
+/// Each frame is rendered when the iterator gets to it, and the last is one
+/// step short of where the first starts, so the sequence loops smoothly.
+///
+/// ```
+/// let mesh = teapot::create_vertices(8, 8);
+/// let vertices = mesh.interleave();
+/// let lighting = teapot::Lighting::default();
+/// let frames: Vec<_> =
+///     teapot::turntable(&vertices, &mesh.indices, &lighting, 32, 24, 4).collect();
+/// assert_eq!(frames.len(), 4);
+/// assert_ne!(frames[0], frames[1]);
+/// ```
+pub fn turntable<'a>(
+    vertices: &'a [Vertex],
+    indices: &'a [u32],
+    lighting: &Lighting,
+    width: u32,
+    height: u32,
+    frames: u32,
+) -> impl Iterator<Item = Image> + 'a {
+    let aspect_ratio = width as f32 / height as f32;
+    let lighting = *lighting;
+    (0..frames).map(move |k| {
+        let time = ORBIT_PERIOD * k as f32 / frames as f32;
+        let mut target = RenderTarget::new(width, height);
+        target.set_lighting(lighting);
+        target.draw_indexed(vertices, indices, &generate_matrix(aspect_ratio, time));
+        target.to_image()
+    })
+}
+
+// Twice the signed area of the triangle abc on the screen
+fn edge(a: Vector3<f32>, b: Vector3<f32>, c: Vector3<f32>) -> f32 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+// Clips a triangle to the near and far planes, 0 <= z <= w, leaving a
+// convex polygon. The sides of the view are left to the rasterizer.
+fn clip(triangle: &[ClipVertex; 3]) -> Vec<ClipVertex> {
+    let mut polygon = triangle.to_vec();
+    let planes: [fn(&Vector4<f32>) -> f32; 2] = [|p| p.z, |p| p.w - p.z];
+    for distance in planes.iter() {
+        let mut clipped = Vec::with_capacity(polygon.len() + 1);
+        for (k, a) in polygon.iter().enumerate() {
+            let b = &polygon[(k + 1) % polygon.len()];
+            let (da, db) = (distance(&a.position), distance(&b.position));
+            if da >= 0.0 {
+                clipped.push(*a);
+            }
+            if (da >= 0.0) != (db >= 0.0) {
+                clipped.push(a.lerp(b, da / (da - db)));
+            }
+        }
+        polygon = clipped;
+    }
+    polygon
+}
+
+// Encodes a linear colour channel as 8 bit sRGB
+fn srgb(linear: f32) -> u8 {
+    let c = if linear < 1.0 { linear.max(0.0) } else { 1.0 };
+    let s = if c <= 0.003_130_8 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (s * 255.0 + 0.5) as u8
+}
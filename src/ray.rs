@@ -0,0 +1,242 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use crate::patch::{BezierPatch, BoundingBox, PatchSet};
+use crate::surface::SurfacePoint;
+use cgmath::*;
+
+// Subdivide patches down to 1/2^MAX_DEPTH of their parameter range before
+// refining hits with Newton's method
+const MAX_DEPTH: u32 = 5;
+
+// Newton's method gives up after this many steps
+const MAX_ITERATIONS: usize = 12;
+
+/// A half line, starting at `origin`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: Point3<f64>,
+    /// Unit direction.
+    pub direction: Vector3<f64>,
+}
+
+impl Ray {
+    /// A ray from `origin` towards `direction`, which needn't be unit
+    /// length.
+    pub fn new(origin: Point3<f64>, direction: Vector3<f64>) -> Ray {
+        Ray {
+            origin,
+            direction: direction.normalize(),
+        }
+    }
+
+    pub fn at(&self, distance: f64) -> Point3<f64> {
+        self.origin + distance * self.direction
+    }
+}
+
+/// Where a ray hits a [`PatchSet`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit {
+    /// Distance along the ray.
+    pub distance: f64,
+    /// Index of the patch that was hit.
+    pub patch: usize,
+    /// The surface there, including `(u, v)` and the normal.
+    pub point: SurfacePoint,
+}
+
+impl BoundingBox {
+    /// The range of distances along `ray` inside the box, if it hits it.
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<(f64, f64)> {
+        let min = self.min.cast::<f64>().unwrap();
+        let max = self.max.cast::<f64>().unwrap();
+        let mut near = 0f64;
+        let mut far = f64::INFINITY;
+        for i in 0..3 {
+            let inv = 1.0 / ray.direction[i];
+            let mut t0 = (min[i] - ray.origin[i]) * inv;
+            let mut t1 = (max[i] - ray.origin[i]) * inv;
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            // NaN from a zero direction inside the slab means no limit
+            if t0 > near {
+                near = t0;
+            }
+            if t1 < far {
+                far = t1;
+            }
+            if near > far {
+                return None;
+            }
+        }
+        Some((near, far))
+    }
+
+    // This box grown by `margin` on every side
+    fn expanded(&self, margin: f32) -> BoundingBox {
+        let m = Vector3::new(margin, margin, margin);
+        BoundingBox {
+            min: self.min - m,
+            max: self.max + m,
+        }
+    }
+}
+
+impl BezierPatch {
+    /// Splits the patch in half along both `u` and `v`, returning the
+    /// quarters in the order `(0, 0)`, `(½, 0)`, `(0, ½)`, `(½, ½)` of the
+    /// `(u, v)` at their first corner.
+    pub fn subdivide(&self) -> [BezierPatch; 4] {
+        let (bottom, top) = self.split_v();
+        let (b0, b1) = bottom.split_u();
+        let (t0, t1) = top.split_u();
+        [b0, b1, t0, t1]
+    }
+
+    // Splits each row at u = ½ with de Casteljau's algorithm
+    fn split_u(&self) -> (BezierPatch, BezierPatch) {
+        let mut left = *self.points();
+        let mut right = left;
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            let (a, b) = split_curve(*l);
+            *l = a;
+            *r = b;
+        }
+        (BezierPatch::new(left), BezierPatch::new(right))
+    }
+
+    fn split_v(&self) -> (BezierPatch, BezierPatch) {
+        let p = self.points();
+        let mut lower = *p;
+        let mut upper = *p;
+        for c in 0..4 {
+            let (a, b) = split_curve([p[0][c], p[1][c], p[2][c], p[3][c]]);
+            for r in 0..4 {
+                lower[r][c] = a[r];
+                upper[r][c] = b[r];
+            }
+        }
+        (BezierPatch::new(lower), BezierPatch::new(upper))
+    }
+
+    /// The nearest place `ray` hits the patch, and the distance to it.
+    ///
+    /// The patch is subdivided, skipping pieces whose bounding boxes the
+    /// ray misses, and the hits on the pieces that are left are refined
+    /// with Newton's method on the exact surface.
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<(f64, SurfacePoint)> {
+        let margin = 1e-4 * self.bounding_box().size().magnitude();
+        let mut best: Option<(f64, SurfacePoint)> = None;
+        let mut stack = vec![(*self, 0.0, 0.0, 1.0, 0)];
+        while let Some((patch, u0, v0, size, depth)) = stack.pop() {
+            let (near, _) = match patch.bounding_box().expanded(margin).intersect_ray(ray) {
+                Some(range) => range,
+                None => continue,
+            };
+            if let Some((best_distance, _)) = best {
+                if near > best_distance {
+                    continue;
+                }
+            }
+
+            if depth < MAX_DEPTH {
+                let half = size / 2.0;
+                let [a, b, c, d] = patch.subdivide();
+                stack.push((a, u0, v0, half, depth + 1));
+                stack.push((b, u0 + half, v0, half, depth + 1));
+                stack.push((c, u0, v0 + half, half, depth + 1));
+                stack.push((d, u0 + half, v0 + half, half, depth + 1));
+                continue;
+            }
+
+            let u = u0 + size / 2.0;
+            let v = v0 + size / 2.0;
+            if let Some(hit) = self.refine_hit(ray, u, v, near) {
+                let closer = match best {
+                    Some((d, _)) => hit.0 < d,
+                    None => true,
+                };
+                if closer {
+                    best = Some(hit);
+                }
+            }
+        }
+        best
+    }
+
+    // Newton's method on P(u, v) - (O + t D) = 0, starting from (u, v, t)
+    fn refine_hit(&self, ray: &Ray, u: f64, v: f64, t: f64) -> Option<(f64, SurfacePoint)> {
+        let scale = self.bounding_box().size().magnitude() as f64;
+        let (mut u, mut v, mut t) = (u, v, t);
+        for _ in 0..MAX_ITERATIONS {
+            let p = self.evaluate(u, v);
+            let f = p.position - ray.at(t);
+            if f.magnitude() <= 1e-9 * scale.max(1.0) {
+                const SLOP: f64 = 1e-7;
+                let inside = (-SLOP..=1.0 + SLOP).contains(&u) && (-SLOP..=1.0 + SLOP).contains(&v);
+                if !inside || t < 0.0 {
+                    return None;
+                }
+                let p = self.evaluate(u.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+                return Some((t, p));
+            }
+
+            let jacobian = Matrix3::from_cols(p.du, p.dv, -ray.direction);
+            let step = jacobian.invert()? * f;
+            u -= step.x;
+            v -= step.y;
+            t -= step.z;
+
+            // wandered far off the patch
+            if !(-0.5..=1.5).contains(&u) || !(-0.5..=1.5).contains(&v) {
+                return None;
+            }
+        }
+        None
+    }
+}
+
+impl PatchSet {
+    /// The nearest place `ray` hits any of the patches.
+    ///
+    /// ```
+    /// use cgmath::{Point3, Vector3};
+    /// use teapot::Ray;
+    ///
+    /// // straight down onto the knob on the lid
+    /// let ray = Ray::new(Point3::new(0.0, 0.0, 10.0), -Vector3::unit_z());
+    /// let hit = teapot::control_points().intersect_ray(&ray).unwrap();
+    /// assert!((hit.distance - (10.0 - 3.15)).abs() < 1e-6);
+    /// ```
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<RayHit> {
+        let mut best: Option<RayHit> = None;
+        for (i, patch) in self.iter().enumerate() {
+            if let Some((distance, point)) = patch.intersect_ray(ray) {
+                let closer = match best {
+                    Some(b) => distance < b.distance,
+                    None => true,
+                };
+                if closer {
+                    best = Some(RayHit {
+                        distance,
+                        patch: i,
+                        point,
+                    });
+                }
+            }
+        }
+        best
+    }
+}
+
+// Splits a cubic Bezier curve at t = ½
+fn split_curve(p: [Point3<f32>; 4]) -> ([Point3<f32>; 4], [Point3<f32>; 4]) {
+    let p01 = p[0].midpoint(p[1]);
+    let p12 = p[1].midpoint(p[2]);
+    let p23 = p[2].midpoint(p[3]);
+    let p012 = p01.midpoint(p12);
+    let p123 = p12.midpoint(p23);
+    let mid = p012.midpoint(p123);
+    ([p[0], p01, p012, mid], [mid, p123, p23, p[3]])
+}
@@ -0,0 +1,331 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use crate::loader::{parse_error, parse_field, LoadError};
+use cgmath::*;
+use std::f32::consts::FRAC_1_SQRT_2 as SQRT_HALF;
+use std::fmt;
+use std::mem::size_of;
+use std::path::Path;
+use std::str::FromStr;
+use zerocopy::AsBytes;
+
+// The viewer's default lighting and material
+pub(crate) const KA: f32 = 0.1;
+pub(crate) const KD: f32 = 0.8;
+// (the original shader declared a ks of 0.75 but never applied it, so the
+// viewer has always drawn the highlight at full strength)
+pub(crate) const KS: f32 = 1.0;
+pub(crate) const SPEC_EXP: f32 = 24.0;
+pub(crate) const LIGHT_VEC: [f32; 3] = [0.5 * SQRT_HALF, SQRT_HALF, 0.866 * SQRT_HALF];
+pub(crate) const LIGHT_COLOR: [f32; 3] = [1.0, 1.0, 1.0];
+pub(crate) const MAT_COLOR: [f32; 3] = [0.5, 0.25, 1.0];
+
+/// How a surface reflects light: an ambient and diffuse term in its own
+/// colour, and a Blinn-Phong highlight in the light's colour.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Material {
+    /// Linear RGB.
+    pub color: [f32; 3],
+    /// Light reflected whichever way the surface faces (`ka`).
+    pub ambient: f32,
+    /// Scale of the Lambertian term (`kd`).
+    pub diffuse: f32,
+    /// Scale of the highlight (`ks`).
+    pub specular: f32,
+    /// Exponent of the highlight; higher is tighter (`specExp`).
+    pub shininess: f32,
+}
+
+impl Default for Material {
+    fn default() -> Material {
+        Material {
+            color: MAT_COLOR,
+            ambient: KA,
+            diffuse: KD,
+            specular: KS,
+            shininess: SPEC_EXP,
+        }
+    }
+}
+
+/// A directional light.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Light {
+    /// Towards the light, in model space. It needn't be a unit vector.
+    pub direction: [f32; 3],
+    /// Linear RGB.
+    pub color: [f32; 3],
+}
+
+impl Default for Light {
+    fn default() -> Light {
+        Light {
+            direction: LIGHT_VEC,
+            color: LIGHT_COLOR,
+        }
+    }
+}
+
+/// Where the lighting is worked out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shading {
+    /// At each vertex, with the colours interpolated across the triangles,
+    /// as in `shader.vsh`. Highlights smear across coarse meshes.
+    Gouraud,
+    /// At each pixel, from normals and positions interpolated across the
+    /// triangles, as in `phong.fsh`. The eye vector runs from the camera to
+    /// each pixel, where Gouraud shading keeps the original shader's single
+    /// view direction.
+    Phong,
+}
+
+impl fmt::Display for Shading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Shading::Gouraud => write!(f, "gouraud"),
+            Shading::Phong => write!(f, "phong"),
+        }
+    }
+}
+
+impl FromStr for Shading {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Shading, String> {
+        match s {
+            "gouraud" => Ok(Shading::Gouraud),
+            "phong" => Ok(Shading::Phong),
+            _ => Err(format!(
+                "unknown shading '{}', expected gouraud or phong",
+                s
+            )),
+        }
+    }
+}
+
+/// The material, light and shading the viewer and
+/// [`RenderTarget`](crate::RenderTarget) draw with.
+///
+/// It can be read from a file with [`load_lighting`], and its `Display`
+/// writes it back out in the same format.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lighting {
+    pub material: Material,
+    pub light: Light,
+    pub shading: Shading,
+}
+
+impl Default for Lighting {
+    fn default() -> Lighting {
+        Lighting {
+            material: Material::default(),
+            light: Light::default(),
+            shading: Shading::Gouraud,
+        }
+    }
+}
+
+/// The viewer's `Locals` uniform block: the transform, material, light and
+/// camera position, laid out by the std140 rules for the shaders.
+///
+/// ```
+/// use zerocopy::AsBytes;
+/// let locals = teapot::Locals::new(&cgmath::One::one(), &teapot::Lighting::default());
+/// assert_eq!(locals.as_bytes().len(), teapot::Locals::SIZE);
+/// ```
+#[repr(C)]
+#[derive(AsBytes, Clone, Copy, Debug, PartialEq)]
+pub struct Locals {
+    transform: [f32; 16],
+    // struct Material
+    material_color: [f32; 3],
+    ambient: f32,
+    diffuse: f32,
+    specular: f32,
+    shininess: f32,
+    _pad0: f32,
+    // struct Light, with its vec3s 16 byte aligned
+    light_direction: [f32; 3],
+    _pad1: f32,
+    light_color: [f32; 3],
+    _pad2: f32,
+    // the camera position with w = 1, or for parallel projections the
+    // direction it looks in with w = 0
+    eye: [f32; 4],
+}
+
+impl Locals {
+    /// Bytes in the uniform buffer.
+    pub const SIZE: usize = size_of::<Locals>();
+
+    /// The block for `transform`, from model to clip space, and
+    /// `lighting`. The light direction is normalized here.
+    pub fn new(transform: &Matrix4<f32>, lighting: &Lighting) -> Locals {
+        let matrix: &[f32; 16] = transform.as_ref();
+        let (material, light) = (&lighting.material, &lighting.light);
+        Locals {
+            transform: *matrix,
+            material_color: material.color,
+            ambient: material.ambient,
+            diffuse: material.diffuse,
+            specular: material.specular,
+            shininess: material.shininess,
+            _pad0: 0.0,
+            light_direction: light_vector(light).into(),
+            _pad1: 0.0,
+            light_color: light.color,
+            _pad2: 0.0,
+            eye: eye_position(transform).into(),
+        }
+    }
+}
+
+impl fmt::Display for Lighting {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Lighting {
+            material,
+            light,
+            shading,
+        } = self;
+        let rgb = |c: &[f32; 3]| format!("{} {} {}", c[0], c[1], c[2]);
+        writeln!(f, "material.color = {}", rgb(&material.color))?;
+        writeln!(f, "material.ambient = {}", material.ambient)?;
+        writeln!(f, "material.diffuse = {}", material.diffuse)?;
+        writeln!(f, "material.specular = {}", material.specular)?;
+        writeln!(f, "material.shininess = {}", material.shininess)?;
+        writeln!(f, "light.direction = {}", rgb(&light.direction))?;
+        writeln!(f, "light.color = {}", rgb(&light.color))?;
+        writeln!(f, "shading = {}", shading)
+    }
+}
+
+/// Parses a lighting file: one `key = value` per line, with vectors as
+/// three numbers, and `#` starting a comment. Anything left out keeps its
+/// default. `shading` is `gouraud` or `phong`.
+///
+/// ```
+/// let text = "# a shinier, green teapot\n\
+///             material.color = 0.2 0.8 0.3\n\
+///             material.shininess = 64\n";
+/// let lighting = teapot::parse_lighting(text).unwrap();
+/// assert_eq!(lighting.material.color, [0.2, 0.8, 0.3]);
+/// assert_eq!(lighting.light, teapot::Light::default());
+/// ```
+pub fn parse_lighting(text: &str) -> Result<Lighting, LoadError> {
+    let mut lighting = Lighting::default();
+    for (i, l) in text.lines().enumerate() {
+        let line = i + 1;
+        let l = l.split('#').next().unwrap_or("").trim();
+        if l.is_empty() {
+            continue;
+        }
+        let mut parts = l.splitn(2, '=');
+        let key = parts.next().unwrap_or("").trim();
+        let value = parts
+            .next()
+            .ok_or_else(|| parse_error(line, format!("expected '{} = value'", key)))?;
+        let values = value.split_whitespace().collect::<Vec<_>>();
+
+        let scalar = || match values.as_slice() {
+            [v] => parse_field::<f32>(line, v, key),
+            _ => Err(parse_error(line, format!("{} needs one number", key))),
+        };
+        let vector = || match values.as_slice() {
+            [x, y, z] => Ok([
+                parse_field::<f32>(line, x, key)?,
+                parse_field::<f32>(line, y, key)?,
+                parse_field::<f32>(line, z, key)?,
+            ]),
+            _ => Err(parse_error(line, format!("{} needs three numbers", key))),
+        };
+        let Lighting {
+            material,
+            light,
+            shading,
+        } = &mut lighting;
+        match key {
+            "material.color" => material.color = vector()?,
+            "material.ambient" => material.ambient = scalar()?,
+            "material.diffuse" => material.diffuse = scalar()?,
+            "material.specular" => material.specular = scalar()?,
+            "material.shininess" => material.shininess = scalar()?,
+            "light.direction" => light.direction = vector()?,
+            "light.color" => light.color = vector()?,
+            "shading" => {
+                *shading = match values.as_slice() {
+                    [v] => v.parse().map_err(|e| parse_error(line, e))?,
+                    _ => return Err(parse_error(line, "shading needs one word".to_string())),
+                }
+            }
+            _ => return Err(parse_error(line, format!("unknown setting '{}'", key))),
+        }
+    }
+    if lighting.light.direction == [0.0; 3] {
+        return Err(parse_error(0, "light.direction can't be zero".to_string()));
+    }
+    Ok(lighting)
+}
+
+/// Reads a lighting file with [`parse_lighting`].
+pub fn load_lighting<P: AsRef<Path>>(path: P) -> Result<Lighting, LoadError> {
+    parse_lighting(&std::fs::read_to_string(path)?)
+}
+
+// The unit vector towards the light
+pub(crate) fn light_vector(light: &Light) -> Vector3<f32> {
+    Vector3::from(light.direction).normalize()
+}
+
+// The direction shader.vsh takes the eye to be looking in everywhere, the
+// view axis taken back through the transform
+pub(crate) fn eye_vector(transform: &Matrix4<f32>) -> Vector3<f32> {
+    let m = Matrix3::from_cols(
+        transform.x.truncate(),
+        transform.y.truncate(),
+        transform.z.truncate(),
+    );
+    let inverse = m.invert().unwrap_or_else(Matrix3::identity);
+    (inverse * Vector3::unit_z()).normalize()
+}
+
+// Where the camera is for `transform`, with w = 1, or for a parallel
+// projection the direction it looks in, with w = 0
+pub(crate) fn eye_position(transform: &Matrix4<f32>) -> Vector4<f32> {
+    // the centre of projection is the point the transform takes to
+    // x = y = w = 0
+    let centre = transform.invert().map(|m| m * Vector4::unit_z());
+    match centre {
+        Some(c) if c.w.abs() > 1e-6 * c.truncate().magnitude() => c / c.w,
+        _ => eye_vector(transform).extend(0.0),
+    }
+}
+
+// The direction from the camera at `eye`, from `eye_position`, to
+// `position`
+pub(crate) fn view_vector(eye: Vector4<f32>, position: Vector3<f32>) -> Vector3<f32> {
+    if eye.w == 0.0 {
+        eye.truncate()
+    } else {
+        (position - eye.truncate()).normalize()
+    }
+}
+
+// The diffuse and specular colours at a point with unit `normal`, looking
+// along the unit vector `eye`, as both pipelines work them out
+pub(crate) fn blinn_phong(
+    normal: Vector3<f32>,
+    eye: Vector3<f32>,
+    lighting: &Lighting,
+) -> (Vector3<f32>, Vector3<f32>) {
+    let (material, light) = (&lighting.material, &lighting.light);
+    let light_vec = light_vector(light);
+    let light_color = Vector3::from(light.color);
+    let lambert = Vector3::from_value(material.ambient)
+        + light_color * (material.diffuse * normal.dot(light_vec).max(0.0));
+    let half = (light_vec - eye).normalize();
+    let specular = material.specular * half.dot(normal).max(0.0).powf(material.shininess);
+    (
+        Vector3::from(material.color).mul_element_wise(lambert.map(|c| c.min(1.0))),
+        light_color * specular,
+    )
+}
@@ -0,0 +1,491 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use crate::basis::{BasisTable, GridSample};
+use crate::mesh::Mesh;
+use crate::patch::{BezierPatch, PatchSet, Side};
+use crate::surface::{bernstein, patch_tangent, surface_normal};
+use cgmath::*;
+use std::collections::HashMap;
+
+// Control points closer than this are the same point
+pub(crate) const TOLERANCE: f32 = 1e-6;
+
+/// The patch on the other side of a shared boundary curve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Neighbour {
+    pub patch: usize,
+    pub side: Side,
+    /// Whether the neighbour runs along the curve in the opposite
+    /// direction.
+    pub reversed: bool,
+}
+
+/// Finds the neighbours of each patch, indexed `[patch][side]` in the order
+/// of [`Side::ALL`]. Two patches are neighbours along a side if the control
+/// points of their boundary curves match to within `tolerance`, in either
+/// direction. Sides that have collapsed to a point have no neighbours.
+pub fn patch_adjacency(patches: &PatchSet, tolerance: f32) -> Vec<[Option<Neighbour>; 4]> {
+    let tolerance2 = tolerance * tolerance;
+    let same = |a: &[Point3<f32>; 4], b: &[Point3<f32>; 4]| {
+        a.iter()
+            .zip(b.iter())
+            .all(|(p, q)| (p - q).magnitude2() <= tolerance2)
+    };
+
+    let mut adjacency = vec![[None; 4]; patches.len()];
+    for (i, p) in patches.iter().enumerate() {
+        for (k, &side) in Side::ALL.iter().enumerate() {
+            let edge = p.edge(side);
+            if is_collapsed(&edge, tolerance2) {
+                continue;
+            }
+            let mut reversed_edge = edge;
+            reversed_edge.reverse();
+
+            'search: for (j, q) in patches.iter().enumerate() {
+                for &other_side in Side::ALL.iter() {
+                    if i == j && side == other_side {
+                        continue;
+                    }
+                    let other = q.edge(other_side);
+                    let reversed = if same(&edge, &other) {
+                        false
+                    } else if same(&reversed_edge, &other) {
+                        true
+                    } else {
+                        continue;
+                    };
+                    adjacency[i][k] = Some(Neighbour {
+                        patch: j,
+                        side: other_side,
+                        reversed,
+                    });
+                    break 'search;
+                }
+            }
+        }
+    }
+    adjacency
+}
+
+/// Tessellates each patch in `patches` at its own `(rows, columns)` of
+/// vertices from `resolutions`, without cracks between neighbouring
+/// patches. Every resolution must be at least 2 X 2.
+///
+/// Each boundary curve shared by two patches is sampled at the higher of
+/// their two resolutions along it, and both patches get exactly the same
+/// vertex positions there. A patch whose grid doesn't match the samples
+/// along one of its sides joins them to its interior with a strip of
+/// triangles instead of the last row or column of its grid.
+///
+/// With the `parallel` feature the patches are tessellated concurrently on
+/// rayon's thread pool. The result is exactly the same as from
+/// [`tesselate_stitched_serial`].
+pub fn tesselate_stitched(patches: &PatchSet, resolutions: &[(usize, usize)]) -> Mesh {
+    stitch(patches, resolutions, cfg!(feature = "parallel"))
+}
+
+/// [`tesselate_stitched`] on the calling thread, whether or not the
+/// `parallel` feature is enabled.
+pub fn tesselate_stitched_serial(patches: &PatchSet, resolutions: &[(usize, usize)]) -> Mesh {
+    stitch(patches, resolutions, false)
+}
+
+/// Number of triangles [`tesselate_stitched`] makes, including the
+/// transition strips, worked out from the same triangulation without
+/// evaluating the surface.
+pub(crate) fn stitched_triangle_count(patches: &PatchSet, resolutions: &[(usize, usize)]) -> usize {
+    assert_eq!(patches.len(), resolutions.len());
+    for &(nr, nc) in resolutions {
+        assert_resolution(nr, nc);
+    }
+    let adjacency = patch_adjacency(patches, TOLERANCE);
+    (0..patches.len())
+        .map(|i| {
+            let edges = shared_edges(patches, resolutions, &adjacency, i, false);
+            let (nr, nc) = resolutions[i];
+            tesselate_with_edges(&patches[i], nr, nc, &edges, None).num_triangles()
+        })
+        .sum()
+}
+
+// The points along each side of patch `i`, in the order of Side::ALL. Each
+// boundary curve shared with a neighbour is sampled at the higher of their
+// two resolutions along it, from the control points of whichever patch
+// comes first, so both get the same positions. Without `sample` the points
+// are all at the origin, for when only their number matters.
+fn shared_edges(
+    patches: &PatchSet,
+    resolutions: &[(usize, usize)],
+    adjacency: &[[Option<Neighbour>; 4]],
+    i: usize,
+    sample: bool,
+) -> [Vec<Point3<f32>>; 4] {
+    // spans along each side, before sharing with neighbours
+    let own_spans = |i: usize, side: Side| {
+        let (nr, nc) = resolutions[i];
+        match side {
+            Side::Bottom | Side::Top => nc - 1,
+            Side::Left | Side::Right => nr - 1,
+        }
+    };
+
+    let mut edges: [Vec<Point3<f32>>; 4] = Default::default();
+    for (k, &side) in Side::ALL.iter().enumerate() {
+        let mut spans = own_spans(i, side);
+        let mut owner = (i, side, false);
+        if let Some(n) = adjacency[i][k] {
+            spans = spans.max(own_spans(n.patch, n.side));
+            if (n.patch, n.side as usize) < (i, side as usize) {
+                owner = (n.patch, n.side, n.reversed);
+            }
+        }
+
+        if !sample {
+            edges[k] = vec![Point3::origin(); spans + 1];
+            continue;
+        }
+        let (j, owner_side, reversed) = owner;
+        let mut points = sample_curve(&patches[j].edge(owner_side), spans);
+        if reversed {
+            points.reverse();
+        }
+        edges[k] = points;
+    }
+    edges
+}
+
+fn stitch(patches: &PatchSet, resolutions: &[(usize, usize)], parallel: bool) -> Mesh {
+    assert_eq!(patches.len(), resolutions.len());
+    // before the spans of any side are worked out from them
+    for &(nr, nc) in resolutions {
+        assert_resolution(nr, nc);
+    }
+    let adjacency = patch_adjacency(patches, TOLERANCE);
+
+    // basis tables for every grid size, shared between patches (a grid can
+    // grow to 3 rows or columns, see tesselate_with_edges)
+    let mut bases = HashMap::new();
+    for n in resolutions.iter().flat_map(|&(nr, nc)| vec![nr, nc, 3]) {
+        bases.entry(n).or_insert_with(|| BasisTable::new(n));
+    }
+
+    let tesselate = |i: usize| {
+        let edges = shared_edges(patches, resolutions, &adjacency, i, true);
+        let (nr, nc) = resolutions[i];
+        tesselate_with_edges(&patches[i], nr, nc, &edges, Some(&bases))
+    };
+
+    // each patch is independent, and they're put together in order, so the
+    // result doesn't depend on which thread did what
+    let pieces: Vec<Mesh> = if parallel {
+        map_parallel(patches.len(), tesselate)
+    } else {
+        (0..patches.len()).map(tesselate).collect()
+    };
+
+    let num_vertices = pieces.iter().map(|m| m.num_vertices()).sum();
+    let num_indices = pieces.iter().map(|m| m.indices.len()).sum();
+    let mut mesh = Mesh::with_capacity(num_vertices, num_indices);
+    for piece in pieces.iter() {
+        mesh.append(piece);
+    }
+    mesh
+}
+
+#[cfg(feature = "parallel")]
+fn map_parallel<F>(n: usize, f: F) -> Vec<Mesh>
+where
+    F: Fn(usize) -> Mesh + Sync + Send,
+{
+    use rayon::prelude::*;
+    (0..n).into_par_iter().map(f).collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn map_parallel<F>(n: usize, f: F) -> Vec<Mesh>
+where
+    F: Fn(usize) -> Mesh,
+{
+    (0..n).map(f).collect()
+}
+
+fn is_collapsed(edge: &[Point3<f32>; 4], tolerance2: f32) -> bool {
+    edge.iter()
+        .all(|p| (p - edge[0]).magnitude2() <= tolerance2)
+}
+
+// A grid needs at least one span each way, so at least 2 rows and columns
+// of vertices
+pub(crate) fn assert_resolution(nr: usize, nc: usize) {
+    assert!(
+        nr >= 2 && nc >= 2,
+        "a patch needs at least 2 rows and columns of vertices, got {} X {}",
+        nr,
+        nc
+    );
+}
+
+// Which sides of a patch have collapsed to a point, such as the top of the
+// lid knob, in the order of Side::ALL
+pub(crate) fn collapsed_sides(patch: &BezierPatch) -> [bool; 4] {
+    let mut collapsed = [false; 4];
+    for (k, &side) in Side::ALL.iter().enumerate() {
+        collapsed[k] = is_collapsed(&patch.edge(side), TOLERANCE * TOLERANCE);
+    }
+    collapsed
+}
+
+// The (u, v) halfway along a side, where a collapsed side's single vertex
+// goes
+pub(crate) fn side_middle(side: Side) -> (f64, f64) {
+    match side {
+        Side::Bottom => (0.5, 0.0),
+        Side::Right => (1.0, 0.5),
+        Side::Top => (0.5, 1.0),
+        Side::Left => (0.0, 0.5),
+    }
+}
+
+// Adds a triangle unless two of its corners are the same vertex, as they
+// are for the cells of the grid next to a collapsed side. The other
+// triangle of each of those cells is left, making a fan around the side's
+// vertex.
+pub(crate) fn push_triangle(mesh: &mut Mesh, a: u32, b: u32, c: u32) {
+    if a != b && b != c && c != a {
+        mesh.indices.extend_from_slice(&[a, b, c]);
+    }
+}
+
+// Points on a cubic Bezier curve at spans + 1 evenly spaced parameters
+fn sample_curve(ctrl: &[Point3<f32>; 4], spans: usize) -> Vec<Point3<f32>> {
+    (0..=spans)
+        .map(|i| {
+            let (b, _) = bernstein(i as f64 / spans as f64);
+            let mut pt = Vector3::new(0f64, 0f64, 0f64);
+            for (w, p) in [b.x, b.y, b.z, b.w].iter().zip(ctrl.iter()) {
+                pt += *w * p.cast::<f64>().unwrap().to_vec();
+            }
+            Point3::from_vec(pt.cast::<f32>().unwrap())
+        })
+        .collect()
+}
+
+// Tessellates a patch on an nr X nc grid, except that the vertices along
+// each side are the given points, which may not match the grid. A side
+// that has collapsed to a point gets a single vertex. `bases` has the
+// basis tables for the grid; without them the surface isn't evaluated, and
+// only the triangles and the vertices' uvs are meaningful. `nr` and `nc`
+// must be at least 2.
+fn tesselate_with_edges(
+    patch: &BezierPatch,
+    nr: usize,
+    nc: usize,
+    edges: &[Vec<Point3<f32>>; 4],
+    bases: Option<&HashMap<usize, BasisTable>>,
+) -> Mesh {
+    assert_resolution(nr, nc);
+    let mut mesh = Mesh::with_capacity(nr * nc, 6 * nr * nc);
+    let mut vertex = |u: f64, v: f64, pos: Point3<f32>| {
+        let (normal, tangent) = match bases {
+            Some(_) => {
+                let sp = patch.evaluate(u, v);
+                (sp.normal, sp.tangent())
+            }
+            None => (Vector3::zero(), Vector4::zero()),
+        };
+        push_vertex(&mut mesh, u, v, pos, normal, tangent)
+    };
+
+    let collapsed = collapsed_sides(patch);
+    let mut apex = [None; 4];
+    for (k, &side) in Side::ALL.iter().enumerate() {
+        if collapsed[k] {
+            let (u, v) = side_middle(side);
+            apex[k] = Some(vertex(u, v, edges[k][0]));
+        }
+    }
+
+    // vertices along the sides, sharing the corners
+    let [bottom, right, top, left] = edges;
+    let param = |i: usize, n: usize| i as f64 / (n - 1) as f64;
+    let mut corner = |a: Option<u32>, b: Option<u32>, u: f64, v: f64, pos: Point3<f32>| {
+        a.or(b).unwrap_or_else(|| vertex(u, v, pos))
+    };
+    let corners = [
+        corner(apex[0], apex[3], 0.0, 0.0, bottom[0]),
+        corner(apex[0], apex[1], 1.0, 0.0, bottom[bottom.len() - 1]),
+        corner(apex[2], apex[1], 1.0, 1.0, top[top.len() - 1]),
+        corner(apex[2], apex[3], 0.0, 1.0, top[0]),
+    ];
+    let mut side = |k: usize,
+                    points: &Vec<Point3<f32>>,
+                    start: u32,
+                    end: u32,
+                    uv: &dyn Fn(f64) -> (f64, f64)| {
+        let n = points.len();
+        if let Some(i) = apex[k] {
+            return vec![i; n];
+        }
+        let mut indices = vec![start];
+        for (i, p) in points.iter().enumerate().take(n - 1).skip(1) {
+            let (u, v) = uv(param(i, n));
+            indices.push(vertex(u, v, *p));
+        }
+        indices.push(end);
+        indices
+    };
+    let bottom = side(0, bottom, corners[0], corners[1], &|t| (t, 0.0));
+    let right = side(1, right, corners[1], corners[2], &|t| (1.0, t));
+    let top = side(2, top, corners[3], corners[2], &|t| (t, 1.0));
+    let left = side(3, left, corners[0], corners[3], &|t| (0.0, t));
+
+    // sides whose samples don't match the grid get a transition strip, and
+    // the regular part of the grid shrinks away from them
+    // (with both sides of a 2 X n grid resampled there's nothing left, so
+    // it has to grow, which can change which sides match it)
+    let (mut nr, mut nc) = (nr, nc);
+    let (resample_bottom, resample_top, resample_left, resample_right) = loop {
+        let flags = (
+            bottom.len() != nc,
+            top.len() != nc,
+            left.len() != nr,
+            right.len() != nr,
+        );
+        if flags.0 && flags.1 && nr < 3 {
+            nr = 3;
+        } else if flags.2 && flags.3 && nc < 3 {
+            nc = 3;
+        } else {
+            break flags;
+        }
+    };
+    let r0 = resample_bottom as usize;
+    let r1 = nr - 1 - resample_top as usize;
+    let c0 = resample_left as usize;
+    let c1 = nc - 1 - resample_right as usize;
+
+    let mut grid = vec![0u32; nr * nc];
+    let mut fill = |r: usize, c: usize, s: Option<&GridSample>| {
+        if r < r0 || r > r1 || c < c0 || c > c1 {
+            return;
+        }
+        grid[r * nc + c] = if r == 0 {
+            bottom[c]
+        } else if r == nr - 1 {
+            top[c]
+        } else if c == 0 {
+            left[r]
+        } else if c == nc - 1 {
+            right[r]
+        } else {
+            let (u, v) = (param(c, nc), param(r, nr));
+            match s {
+                Some(s) => {
+                    let normal = surface_normal(patch, s.du, s.dv, s.twist, u, v);
+                    let tangent = patch_tangent(normal, s.du, s.dv, s.twist, v);
+                    let pos = Point3::from_vec(s.position.cast::<f32>().unwrap());
+                    push_vertex(&mut mesh, u, v, pos, normal, tangent)
+                }
+                None => push_vertex(
+                    &mut mesh,
+                    u,
+                    v,
+                    Point3::origin(),
+                    Vector3::zero(),
+                    Vector4::zero(),
+                ),
+            }
+        };
+    };
+    match bases {
+        Some(bases) => {
+            patch.for_each_grid_sample(&bases[&nr], &bases[&nc], |r, c, s| fill(r, c, Some(s)))
+        }
+        None => {
+            for r in 0..nr {
+                for c in 0..nc {
+                    fill(r, c, None);
+                }
+            }
+        }
+    }
+
+    for r in r0..r1 {
+        for c in c0..c1 {
+            let i = |r: usize, c: usize| grid[r * nc + c];
+            push_triangle(&mut mesh, i(r, c), i(r, c + 1), i(r + 1, c + 1));
+            push_triangle(&mut mesh, i(r, c), i(r + 1, c + 1), i(r + 1, c));
+        }
+    }
+
+    if resample_bottom {
+        let inner: Vec<u32> = (c0..=c1).map(|c| grid[r0 * nc + c]).collect();
+        strip(&mut mesh, &bottom, &inner, 0);
+    }
+    if resample_top {
+        let inner: Vec<u32> = (c0..=c1).map(|c| grid[r1 * nc + c]).collect();
+        strip(&mut mesh, &top, &inner, 0);
+    }
+    if resample_left {
+        let inner: Vec<u32> = (r0..=r1).map(|r| grid[r * nc + c0]).collect();
+        strip(&mut mesh, &left, &inner, 1);
+    }
+    if resample_right {
+        let inner: Vec<u32> = (r0..=r1).map(|r| grid[r * nc + c1]).collect();
+        strip(&mut mesh, &right, &inner, 1);
+    }
+
+    mesh.patch_ranges.push(0..mesh.indices.len());
+    mesh
+}
+
+// Adds a vertex to the mesh, returning its index
+pub(crate) fn push_vertex(
+    mesh: &mut Mesh,
+    u: f64,
+    v: f64,
+    pos: Point3<f32>,
+    normal: Vector3<f64>,
+    tangent: Vector4<f64>,
+) -> u32 {
+    mesh.vertices.push([pos.x, pos.y, pos.z, 1.0]);
+    mesh.normals.push(normal.cast::<f32>().unwrap().into());
+    mesh.uvs.push([u as f32, v as f32]);
+    mesh.tangents.push(tangent.cast::<f32>().unwrap().into());
+    (mesh.vertices.len() - 1) as u32
+}
+
+// Triangulates the band between two polylines that run the same way along
+// the patch, ordering vertices by their uv coordinate `axis`. Triangles are
+// wound counter-clockwise in uv, like the rest of the grid.
+fn strip(mesh: &mut Mesh, outer: &[u32], inner: &[u32], axis: usize) {
+    let t = |i: u32| mesh.uvs[i as usize][axis];
+    let mut triangles = Vec::with_capacity(3 * (outer.len() + inner.len()));
+    let (mut i, mut j) = (0, 0);
+    while i + 1 < outer.len() || j + 1 < inner.len() {
+        let advance_outer =
+            j + 1 == inner.len() || (i + 1 < outer.len() && t(outer[i + 1]) <= t(inner[j + 1]));
+        if advance_outer {
+            triangles.push([outer[i], outer[i + 1], inner[j]]);
+            i += 1;
+        } else {
+            triangles.push([outer[i], inner[j + 1], inner[j]]);
+            j += 1;
+        }
+    }
+
+    for [a, b, c] in triangles {
+        let (pa, pb, pc) = (
+            mesh.uvs[a as usize],
+            mesh.uvs[b as usize],
+            mesh.uvs[c as usize],
+        );
+        let area = (pb[0] - pa[0]) * (pc[1] - pa[1]) - (pc[0] - pa[0]) * (pb[1] - pa[1]);
+        if area >= 0.0 {
+            push_triangle(mesh, a, b, c);
+        } else {
+            push_triangle(mesh, a, c, b);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use crate::mesh::Mesh;
+use cgmath::*;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Writes `mesh` as a binary STL file.
+///
+/// STL has no shared vertices, normals per vertex or texture coordinates,
+/// so each triangle is written with its own three corners and the normal of
+/// its plane. A triangle with no area, which the teapot's tessellations
+/// don't make but other meshes might, gets a zero normal, which readers
+/// take to mean "work it out from the winding".
+///
+/// ```
+/// let mesh = teapot::create_vertices(4, 4);
+/// let mut stl = Vec::new();
+/// teapot::write_stl(&mesh, &mut stl).unwrap();
+/// assert_eq!(stl.len(), 84 + 50 * mesh.num_triangles());
+/// ```
+pub fn write_stl<W: Write>(mesh: &Mesh, out: W) -> io::Result<()> {
+    let mut out = out;
+    let mut header = [0u8; 80];
+    let title = b"teapot";
+    header[..title.len()].copy_from_slice(title);
+    out.write_all(&header)?;
+    out.write_all(&(mesh.num_triangles() as u32).to_le_bytes())?;
+
+    for (normal, corners) in facets(mesh) {
+        for x in normal.iter().chain(corners.iter().flatten()) {
+            out.write_all(&x.to_le_bytes())?;
+        }
+        // attribute byte count, which nothing uses
+        out.write_all(&[0, 0])?;
+    }
+    out.flush()
+}
+
+/// Writes `mesh` as an ASCII STL file, with the same facets as
+/// [`write_stl`].
+pub fn write_stl_ascii<W: Write>(mesh: &Mesh, out: W) -> io::Result<()> {
+    let mut out = out;
+    writeln!(out, "solid teapot")?;
+    for (n, corners) in facets(mesh) {
+        writeln!(out, "  facet normal {} {} {}", n[0], n[1], n[2])?;
+        writeln!(out, "    outer loop")?;
+        for p in corners.iter() {
+            writeln!(out, "      vertex {} {} {}", p[0], p[1], p[2])?;
+        }
+        writeln!(out, "    endloop")?;
+        writeln!(out, "  endfacet")?;
+    }
+    writeln!(out, "endsolid teapot")?;
+    out.flush()
+}
+
+/// Writes `mesh` to a binary STL file at `path`.
+pub fn save_stl<P: AsRef<Path>>(mesh: &Mesh, path: P) -> io::Result<()> {
+    write_stl(mesh, BufWriter::new(File::create(path)?))
+}
+
+/// Writes `mesh` to an ASCII STL file at `path`.
+pub fn save_stl_ascii<P: AsRef<Path>>(mesh: &Mesh, path: P) -> io::Result<()> {
+    write_stl_ascii(mesh, BufWriter::new(File::create(path)?))
+}
+
+// Each triangle's unit normal and corners
+fn facets(mesh: &Mesh) -> impl Iterator<Item = ([f32; 3], [[f32; 3]; 3])> + '_ {
+    mesh.indices.chunks(3).map(move |tri| {
+        let corner = |i: u32| {
+            let v = mesh.vertices[i as usize];
+            [v[0], v[1], v[2]]
+        };
+        let corners = [corner(tri[0]), corner(tri[1]), corner(tri[2])];
+
+        let [a, b, c] = corners;
+        let (a, b, c) = (Vector3::from(a), Vector3::from(b), Vector3::from(c));
+        let normal = (b - a).cross(c - a);
+        let normal = if normal.magnitude2() > 0.0 {
+            normal.normalize()
+        } else {
+            Vector3::zero()
+        };
+        (normal.into(), corners)
+    })
+}
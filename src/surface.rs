@@ -0,0 +1,323 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use crate::patch::BezierPatch;
+use cgmath::*;
+
+/// The surface of a [`BezierPatch`] at one `(u, v)`, from
+/// [`BezierPatch::evaluate`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SurfacePoint {
+    pub u: f64,
+    pub v: f64,
+    pub position: Point3<f64>,
+    /// First partials, `∂P/∂u` and `∂P/∂v`.
+    pub du: Vector3<f64>,
+    pub dv: Vector3<f64>,
+    /// Second partials, `∂²P/∂u²`, `∂²P/∂u∂v` and `∂²P/∂v²`.
+    pub duu: Vector3<f64>,
+    pub duv: Vector3<f64>,
+    pub dvv: Vector3<f64>,
+    /// Unit normal, in the direction of `du × dv`. This is well defined
+    /// even where one of the partials vanishes, such as where a row of
+    /// control points has collapsed to a point.
+    pub normal: Vector3<f64>,
+}
+
+/// Curvature of a surface at a point, from [`SurfacePoint::curvature`].
+///
+/// Curvatures are positive where the surface bends towards its normal, so
+/// the outside of a sphere with outward normals has negative curvatures
+/// and positive Gaussian curvature.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Curvature {
+    /// The principal curvatures, smallest first.
+    pub principal: [f64; 2],
+    /// Unit tangents along which the principal curvatures occur.
+    pub directions: [Vector3<f64>; 2],
+    /// Product of the principal curvatures.
+    pub gaussian: f64,
+    /// Average of the principal curvatures.
+    pub mean: f64,
+}
+
+impl BezierPatch {
+    /// Evaluates the surface and its derivatives at `(u, v)`.
+    ///
+    /// ```
+    /// // the centre of the bottom of the teapot, where du vanishes
+    /// let patches = teapot::control_points();
+    /// let p = patches[28].evaluate(0.5, 0.0);
+    /// assert_eq!(p.position, cgmath::Point3::new(0.0, 0.0, 0.0));
+    /// assert!((p.normal.z + 1.0).abs() < 1e-12);
+    /// ```
+    pub fn evaluate(&self, u: f64, v: f64) -> SurfacePoint {
+        let (up, du, duu) = bernstein2(u);
+        let (vp, dv, dvv) = bernstein2(v);
+
+        let cpts = self.points();
+        let zero = Vector3::new(0f64, 0f64, 0f64);
+        let (mut pt, mut pu, mut pv) = (zero, zero, zero);
+        let (mut puu, mut puv, mut pvv) = (zero, zero, zero);
+        for a in 0..4 {
+            for b in 0..4 {
+                let cpt = cpts[a][b].cast::<f64>().unwrap().to_vec();
+                pt += up[b] * vp[a] * cpt;
+                pu += du[b] * vp[a] * cpt;
+                pv += up[b] * dv[a] * cpt;
+                puu += duu[b] * vp[a] * cpt;
+                puv += du[b] * dv[a] * cpt;
+                pvv += up[b] * dvv[a] * cpt;
+            }
+        }
+
+        let normal = surface_normal(self, pu, pv, puv, u, v);
+
+        SurfacePoint {
+            u,
+            v,
+            position: Point3::from_vec(pt),
+            du: pu,
+            dv: pv,
+            duu: puu,
+            duv: puv,
+            dvv: pvv,
+            normal,
+        }
+    }
+}
+
+impl SurfacePoint {
+    /// Unit tangent along `u`, perpendicular to the normal, with the
+    /// handedness of the tangent frame in `w`: the bitangent, along `v`, is
+    /// `w` times `normal × tangent`. This is the form glTF and most normal
+    /// mapping shaders expect.
+    pub fn tangent(&self) -> Vector4<f64> {
+        patch_tangent(self.normal, self.du, self.dv, self.duv, self.v)
+    }
+
+    /// The curvature of the surface here, or `None` where the surface
+    /// isn't regular because `du` and `dv` are parallel or vanish.
+    pub fn curvature(&self) -> Option<Curvature> {
+        // first fundamental form
+        let e = self.du.dot(self.du);
+        let f = self.du.dot(self.dv);
+        let g = self.dv.dot(self.dv);
+        let det = e * g - f * f;
+        if det <= 1e-12 * e.max(g) * e.max(g) {
+            return None;
+        }
+
+        // second fundamental form
+        let l = self.duu.dot(self.normal);
+        let m = self.duv.dot(self.normal);
+        let n = self.dvv.dot(self.normal);
+
+        let gaussian = (l * n - m * m) / det;
+        let mean = (e * n - 2.0 * f * m + g * l) / (2.0 * det);
+        let disc = (mean * mean - gaussian).max(0.0).sqrt();
+        let principal = [mean - disc, mean + disc];
+
+        // The principal directions are the eigenvectors of the shape
+        // operator, solving (II - k I) [a b]ᵀ = 0 for each curvature k.
+        // Pick whichever row of the matrix is better conditioned.
+        let tiny = 1e-12 * e.max(g).sqrt();
+        let direction = |k: f64| {
+            let row1 = (m - k * f, k * e - l);
+            let row2 = (n - k * g, k * f - m);
+            let (a, b) = if row1.0.abs() + row1.1.abs() >= row2.0.abs() + row2.1.abs() {
+                row1
+            } else {
+                row2
+            };
+            let dir = a * self.du + b * self.dv;
+            if dir.magnitude() > tiny {
+                Some(dir.normalize())
+            } else {
+                None
+            }
+        };
+
+        // at an umbilic every direction is principal
+        let directions = match (direction(principal[0]), direction(principal[1])) {
+            (Some(d1), Some(d2)) => [d1, d2],
+            (Some(d1), None) => [d1, self.normal.cross(d1)],
+            (None, Some(d2)) => [d2.cross(self.normal), d2],
+            (None, None) => {
+                let d1 = self.du.normalize();
+                [d1, self.normal.cross(d1)]
+            }
+        };
+
+        Some(Curvature {
+            principal,
+            directions,
+            gaussian,
+            mean,
+        })
+    }
+}
+
+// Cubic Bernstein polynomials at t, and their derivatives
+pub(crate) fn bernstein(t: f64) -> (Vector4<f64>, Vector4<f64>) {
+    let t2 = t * t;
+    let t3 = t * t2;
+    let mt = 1.0 - t;
+    let mt2 = mt * mt;
+    let mt3 = mt * mt2;
+
+    let b = Vector4::new(mt3, 3.0 * mt2 * t, 3.0 * mt * t2, t3);
+    let db = Vector4::new(
+        -3.0 + 6.0 * t - 3.0 * t2,
+        3.0 * (1.0 - 4.0 * t + 3.0 * t2),
+        3.0 * (2.0 * t - 3.0 * t2),
+        3.0 * t2,
+    );
+    (b, db)
+}
+
+// Cubic Bernstein polynomials at t, and their first and second derivatives
+fn bernstein2(t: f64) -> (Vector4<f64>, Vector4<f64>, Vector4<f64>) {
+    let (b, db) = bernstein(t);
+    let ddb = Vector4::new(6.0 * (1.0 - t), 18.0 * t - 12.0, 6.0 - 18.0 * t, 6.0 * t);
+    (b, db, ddb)
+}
+
+// Unit normal of the patch at (u, v), from the partials there if possible
+pub(crate) fn surface_normal(
+    patch: &BezierPatch,
+    du: Vector3<f64>,
+    dv: Vector3<f64>,
+    twist: Vector3<f64>,
+    u: f64,
+    v: f64,
+) -> Vector3<f64> {
+    patch_normal(du, dv, twist, u, v)
+        .or_else(|| nudged_normal(patch, u, v))
+        .unwrap_or_else(Vector3::unit_z)
+}
+
+// Unit normal from the partials at (u, v), or None if it can't be found
+// from them.
+//
+// Where control points coincide one of the tangents vanishes. The lid knob
+// and the bottom each have a row of control points collapsed to a single
+// point, so tan1 is zero all along v = 0. The normal there is the limit of
+// the cross product as we move into the patch. Moving away from the
+// collapsed row tan1 grows like the twist vector, so we use that instead,
+// with the sign that points it into the patch.
+fn patch_normal(
+    tan1: Vector3<f64>,
+    tan2: Vector3<f64>,
+    twist: Vector3<f64>,
+    u: f64,
+    v: f64,
+) -> Option<Vector3<f64>> {
+    let scale = tan1
+        .magnitude2()
+        .max(tan2.magnitude2())
+        .max(twist.magnitude2());
+    let tiny = 1e-16 * scale;
+    if scale == 0.0 || !scale.is_finite() {
+        return None;
+    }
+
+    let t1 = if tan1.magnitude2() > tiny {
+        tan1
+    } else if v < 0.5 {
+        twist
+    } else {
+        -twist
+    };
+    let t2 = if tan2.magnitude2() > tiny {
+        tan2
+    } else if u < 0.5 {
+        twist
+    } else {
+        -twist
+    };
+
+    let normal = t1.cross(t2);
+    if normal.magnitude2() > tiny * scale {
+        Some(normal.normalize())
+    } else {
+        None
+    }
+}
+
+// Unit tangent along u and handedness for SurfacePoint::tangent, given the
+// unit normal. Where du vanishes the twist stands in for it, as in
+// patch_normal.
+pub(crate) fn patch_tangent(
+    normal: Vector3<f64>,
+    du: Vector3<f64>,
+    dv: Vector3<f64>,
+    twist: Vector3<f64>,
+    v: f64,
+) -> Vector4<f64> {
+    let scale = du.magnitude2().max(dv.magnitude2()).max(twist.magnitude2());
+    let tiny = 1e-16 * scale;
+    let along_u = if du.magnitude2() > tiny {
+        du
+    } else if v < 0.5 {
+        twist
+    } else {
+        -twist
+    };
+
+    // remove any part along the normal
+    let mut tangent = along_u - normal * normal.dot(along_u);
+    if tangent.magnitude2() <= tiny || !tangent.magnitude2().is_finite() {
+        tangent = dv.cross(normal);
+    }
+    if tangent.magnitude2() <= tiny || !tangent.magnitude2().is_finite() {
+        tangent = any_perpendicular(normal);
+    }
+    let tangent = tangent.normalize();
+
+    let w = if normal.cross(tangent).dot(dv) < 0.0 {
+        -1.0
+    } else {
+        1.0
+    };
+    tangent.extend(w)
+}
+
+// Some unit vector perpendicular to the unit vector n
+pub(crate) fn any_perpendicular<S: BaseFloat>(n: Vector3<S>) -> Vector3<S> {
+    let axis = if n.x.abs() < n.y.abs() && n.x.abs() < n.z.abs() {
+        Vector3::unit_x()
+    } else if n.y.abs() < n.z.abs() {
+        Vector3::unit_y()
+    } else {
+        Vector3::unit_z()
+    };
+    n.cross(axis).normalize()
+}
+
+// Last resort for patch_normal: take the normal from a point slightly
+// inside the patch.
+fn nudged_normal(patch: &BezierPatch, u: f64, v: f64) -> Option<Vector3<f64>> {
+    let cpts = patch.points();
+    for &step in [1e-4, 1e-3, 1e-2].iter() {
+        let nu = u + (0.5 - u) * step;
+        let nv = v + (0.5 - v) * step;
+        let (up, du) = bernstein(nu);
+        let (vp, dv) = bernstein(nv);
+
+        let mut tan1 = Vector3::new(0f64, 0f64, 0f64);
+        let mut tan2 = Vector3::new(0f64, 0f64, 0f64);
+        for a in 0..4 {
+            for b in 0..4 {
+                let cpt = cpts[a][b].cast::<f64>().unwrap().to_vec();
+                tan1 += du[b] * vp[a] * cpt;
+                tan2 += up[b] * dv[a] * cpt;
+            }
+        }
+
+        let normal = tan1.cross(tan2);
+        if normal.magnitude2() > 0.0 && normal.magnitude2().is_finite() {
+            return Some(normal.normalize());
+        }
+    }
+    None
+}
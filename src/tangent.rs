@@ -0,0 +1,60 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use crate::mesh::Mesh;
+use crate::surface::any_perpendicular;
+use cgmath::*;
+
+impl Mesh {
+    /// Makes tangents from the texture coordinates, for meshes that don't
+    /// come from patches and so have no exact derivatives to take them
+    /// from.
+    ///
+    /// Each triangle gives the directions in which `u` and `v` increase
+    /// across it. Those are summed over the triangles around each vertex,
+    /// and the `u` direction is made perpendicular to the vertex normal to
+    /// give the tangent. Vertices whose texture coordinates don't vary get
+    /// an arbitrary tangent perpendicular to their normal.
+    pub fn generate_tangents(&mut self) {
+        let zero = Vector3::zero();
+        let mut along_u = vec![zero; self.vertices.len()];
+        let mut along_v = vec![zero; self.vertices.len()];
+        for tri in self.indices.chunks(3) {
+            let p = |k: usize| {
+                let v = self.vertices[tri[k] as usize];
+                Vector3::new(v[0], v[1], v[2])
+            };
+            let t = |k: usize| Vector2::from(self.uvs[tri[k] as usize]);
+            let (e1, e2) = (p(1) - p(0), p(2) - p(0));
+            let (d1, d2) = (t(1) - t(0), t(2) - t(0));
+
+            // solve [e1 e2] = [dp/du dp/dv] [d1 d2]
+            let det = d1.x * d2.y - d2.x * d1.y;
+            if det == 0.0 || !det.is_finite() {
+                continue;
+            }
+            let du = (e1 * d2.y - e2 * d1.y) / det;
+            let dv = (e2 * d1.x - e1 * d2.x) / det;
+            for &i in tri {
+                along_u[i as usize] += du;
+                along_v[i as usize] += dv;
+            }
+        }
+
+        self.tangents = self
+            .normals
+            .iter()
+            .zip(along_u.iter().zip(along_v.iter()))
+            .map(|(&n, (&du, &dv))| {
+                let n = Vector3::from(n);
+                let t = du - n * n.dot(du);
+                let t = if t.magnitude2() > 0.0 && t.magnitude2().is_finite() {
+                    t.normalize()
+                } else {
+                    any_perpendicular(n)
+                };
+                let w = if n.cross(t).dot(dv) < 0.0 { -1.0 } else { 1.0 };
+                t.extend(w).into()
+            })
+            .collect();
+    }
+}
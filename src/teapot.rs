@@ -1,1050 +1,981 @@
 ﻿// Teapot demo © 2020 RustyTriangles LLC
 
+use crate::basis::BasisTable;
+use crate::mesh::Mesh;
+use crate::patch::{BezierPatch, PatchSet, Side};
+use crate::stitch::{
+    assert_resolution, collapsed_sides, push_triangle, push_vertex, side_middle, tesselate_stitched,
+};
+use crate::surface::{patch_tangent, surface_normal};
 use cgmath::*;
+use std::ops::Range;
 
-pub fn create_vertices(
-    nr: usize,
-    nc: usize,
-) -> (Vec<[f32; 4]>, Vec<[f32; 3]>, Vec<[f32; 2]>, Vec<u32>) {
-    let cpts = control_points();
-    let mut vertices: Vec<[f32; 4]> = Vec::with_capacity(12);
-    let mut normals: Vec<[f32; 3]> = Vec::with_capacity(12);
-    let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(12);
-    let mut indices: Vec<u32> = Vec::with_capacity(12);
-    for i in 0..32 {
-        let base = vertices.len() as u32;
+/// Number of patches in the teapot.
+pub const NUM_PATCHES: usize = 32;
 
-        // tesselate this patch
-        let this_patch = &cpts[i];
-        let (patch_vertices, patch_normals, patch_uvs, patch_indices) =
-            tesselate_patch(this_patch, nr, nc);
+/// The parts of the teapot, and the range of [`control_points`] that makes
+/// up each of them.
+pub const PATCH_GROUPS: [(&str, Range<usize>); 5] = [
+    ("body", 0..12),
+    ("handle", 12..16),
+    ("spout", 16..20),
+    ("lid", 20..28),
+    ("bottom", 28..32),
+];
 
-        // append to buffers
-        vertices.extend_from_slice(&patch_vertices);
-        normals.extend_from_slice(&patch_normals);
-        uvs.extend_from_slice(&patch_uvs);
-
-        // indices have to be rebased
-        for j in patch_indices {
-            indices.push(base + j);
-        }
-    }
-    (vertices, normals, uvs, indices)
+/// Tessellates the whole teapot, sampling each patch at `nr` X `nc`
+/// vertices. Patches are not welded, so vertices along the seams between
+/// them are duplicated. `nr` and `nc` must be at least 2.
+pub fn create_vertices(nr: usize, nc: usize) -> Mesh {
+    tesselate_patches(&control_points(), nr, nc)
 }
 
-// Tesselate a cubic Bezier patch at nr X nc vertices
-fn tesselate_patch(
-    cpts: &Vec<Vec<Point3<f32>>>,
-    nr: usize,
-    nc: usize,
-) -> (Vec<[f32; 4]>, Vec<[f32; 3]>, Vec<[f32; 2]>, Vec<u32>) {
-    let mut verts: Vec<[f32; 4]> = Vec::with_capacity(nr * nc);
-    let mut norms: Vec<[f32; 3]> = Vec::with_capacity(nr * nc);
-    let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(nr * nc);
-
-    for r in 0..nr {
-        let v = r as f64 / (nr - 1) as f64;
-        let v2 = v * v;
-        let v3 = v * v2;
-        let mv = 1.0 - v;
-        let mv2 = mv * mv;
-        let mv3 = mv * mv2;
-
-        let vp = Vector4::new(mv3, 3.0 * mv2 * v, 3.0 * mv * v2, v3);
-        let dv = Vector4::new(
-            -3.0 + 6.0 * v - 3.0 * v2,
-            3.0 * (1.0 - 4.0 * v + 3.0 * v2),
-            3.0 * (2.0 * v - 3.0 * v2),
-            3.0 * v2,
-        );
-
-        for c in 0..nc {
-            let u = c as f64 / (nc - 1) as f64;
-            let u2 = u * u;
-            let u3 = u * u2;
-            let mu = 1.0 - u;
-            let mu2 = mu * mu;
-            let mu3 = mu * mu2;
-
-            let up = Vector4::new(mu3, 3.0 * mu2 * u, 3.0 * mu * u2, u3);
-            let du = Vector4::new(
-                -3.0 + 6.0 * u - 3.0 * u2,
-                3.0 * (1.0 - 4.0 * u + 3.0 * u2),
-                3.0 * (2.0 * u - 3.0 * u2),
-                3.0 * u2,
-            );
+/// Tessellates every patch in `patches` at `nr` X `nc` vertices and
+/// concatenates the results. Where a patch's rows meet a neighbour's
+/// columns the two are stitched together without cracks, as in
+/// [`tesselate_stitched`].
+pub fn tesselate_patches(patches: &PatchSet, nr: usize, nc: usize) -> Mesh {
+    tesselate_stitched(patches, &vec![(nr, nc); patches.len()])
+}
 
-            // Basis matrix times vectors of powers of parameters. See post
-            // on my Mathworks blog for details:
-            // https://blogs.mathworks.com/graphics/2015/05/12/patch-work/
-            let w = Matrix4::new(
-                up[0] * vp[0],
-                up[1] * vp[0],
-                up[2] * vp[0],
-                up[3] * vp[0],
-                up[0] * vp[1],
-                up[1] * vp[1],
-                up[2] * vp[1],
-                up[3] * vp[1],
-                up[0] * vp[2],
-                up[1] * vp[2],
-                up[2] * vp[2],
-                up[3] * vp[2],
-                up[0] * vp[3],
-                up[1] * vp[3],
-                up[2] * vp[3],
-                up[3] * vp[3],
-            );
+/// Tessellates a cubic Bezier patch at `nr` X `nc` vertices. Rows of the
+/// grid run along `v` and columns along `u`. A side that has collapsed to
+/// a point, such as the top of the lid knob, gets a single vertex at the
+/// apex of a fan of triangles instead of a row of zero-area triangles.
+/// `nr` and `nc` must be at least 2.
+pub fn tesselate_patch(patch: &BezierPatch, nr: usize, nc: usize) -> Mesh {
+    assert_resolution(nr, nc);
+    tesselate_patch_with(patch, &BasisTable::new(nr), &BasisTable::new(nc))
+}
 
-            // First partials
-            let dwdv = Matrix4::new(
-                du[0] * vp[0],
-                du[1] * vp[0],
-                du[2] * vp[0],
-                du[3] * vp[0],
-                du[0] * vp[1],
-                du[1] * vp[1],
-                du[2] * vp[1],
-                du[3] * vp[1],
-                du[0] * vp[2],
-                du[1] * vp[2],
-                du[2] * vp[2],
-                du[3] * vp[2],
-                du[0] * vp[3],
-                du[1] * vp[3],
-                du[2] * vp[3],
-                du[3] * vp[3],
-            );
+/// Tessellates a cubic Bezier patch with a row of vertices for each sample
+/// in `rows` and a column for each in `cols`. Build the tables once to
+/// tessellate many patches at the same size. Each table must have at least
+/// 2 samples.
+pub fn tesselate_patch_with(patch: &BezierPatch, rows: &BasisTable, cols: &BasisTable) -> Mesh {
+    let (nr, nc) = (rows.len(), cols.len());
+    assert_resolution(nr, nc);
+    let mut mesh = Mesh::with_capacity(nr * nc, 6 * (nr - 1) * (nc - 1));
 
-            let dwdu = Matrix4::new(
-                up[0] * dv[0],
-                up[1] * dv[0],
-                up[2] * dv[0],
-                up[3] * dv[0],
-                up[0] * dv[1],
-                up[1] * dv[1],
-                up[2] * dv[1],
-                up[3] * dv[1],
-                up[0] * dv[2],
-                up[1] * dv[2],
-                up[2] * dv[2],
-                up[3] * dv[2],
-                up[0] * dv[3],
-                up[1] * dv[3],
-                up[2] * dv[3],
-                up[3] * dv[3],
-            );
+    let collapsed = collapsed_sides(patch);
+    let mut apex = [None; 4];
+    for (k, &side) in Side::ALL.iter().enumerate() {
+        if collapsed[k] {
+            let (u, v) = side_middle(side);
+            let sp = patch.evaluate(u, v);
+            let pos = sp.position.cast::<f32>().unwrap();
+            apex[k] = Some(push_vertex(&mut mesh, u, v, pos, sp.normal, sp.tangent()));
+        }
+    }
+    let mut grid = vec![0u32; nr * nc];
 
-            let mut pt = Vector3::new(0f64, 0f64, 0f64);
-            let mut tan1 = Vector3::new(0f64, 0f64, 0f64);
-            let mut tan2 = Vector3::new(0f64, 0f64, 0f64);
-            for a in 0..4 {
-                for b in 0..4 {
-                    let cpt = Vector3::new(
-                        cpts[a][b].x as f64,
-                        cpts[a][b].y as f64,
-                        cpts[a][b].z as f64,
-                    );
+    // The surface is B(v)ᵀ · P · B(u), with B the Bernstein basis. See post
+    // on my Mathworks blog for details:
+    // https://blogs.mathworks.com/graphics/2015/05/12/patch-work/
+    patch.for_each_grid_sample(rows, cols, |r, c, s| {
+        // in the order of Side::ALL
+        let sides = [r == 0, c == nc - 1, r == nr - 1, c == 0];
+        if let Some(i) = (0..4).filter(|&k| sides[k]).find_map(|k| apex[k]) {
+            grid[r * nc + c] = i;
+            return;
+        }
+        grid[r * nc + c] = mesh.vertices.len() as u32;
 
-                    pt += w[a][b] * cpt;
-                    tan1 += dwdv[a][b] * cpt;
-                    tan2 += dwdu[a][b] * cpt;
-                }
-            }
+        let (u, v) = (cols.param(c), rows.param(r));
+        let pt = s.position;
+        mesh.vertices
+            .push([pt[0] as f32, pt[1] as f32, pt[2] as f32, 1.0]);
 
-            verts.push([pt[0] as f32, pt[1] as f32, pt[2] as f32, 1.0]);
+        // normal is cross of the 2 tangents
+        let normal = surface_normal(patch, s.du, s.dv, s.twist, u, v);
+        mesh.normals
+            .push([normal[0] as f32, normal[1] as f32, normal[2] as f32]);
 
-            // normal is cross of the 2 tangents
-            // @todo handle the coincident control points case
-            let normal = tan1.normalize().cross(tan2.normalize());
-            norms.push([normal[0] as f32, normal[1] as f32, normal[2] as f32]);
+        mesh.uvs.push([u as f32, v as f32]);
 
-            let uv = [u as f32, v as f32];
-            uvs.push(uv);
-        }
-    }
+        // tangent along u, for normal mapping
+        let tangent = patch_tangent(normal, s.du, s.dv, s.twist, v);
+        mesh.tangents.push(tangent.cast::<f32>().unwrap().into());
+    });
 
-    let mut indices: Vec<u32> = Vec::with_capacity(2 * 3 * (nr - 1) * (nc - 1));
     for r in 0..(nr - 1) {
         for c in 0..(nc - 1) {
-            indices.push((r * nc + c) as u32);
-            indices.push((r * nc + c + 1) as u32);
-            indices.push(((r + 1) * nc + c + 1) as u32);
-            indices.push((r * nc + c) as u32);
-            indices.push(((r + 1) * nc + c + 1) as u32);
-            indices.push(((r + 1) * nc + c) as u32);
+            let i = |r: usize, c: usize| grid[r * nc + c];
+            push_triangle(&mut mesh, i(r, c), i(r, c + 1), i(r + 1, c + 1));
+            push_triangle(&mut mesh, i(r, c), i(r + 1, c + 1), i(r + 1, c));
         }
     }
 
-    (verts, norms, uvs, indices)
+    mesh.patch_ranges.push(0..mesh.indices.len());
+    mesh
 }
 
-fn control_points() -> Vec<Vec<Vec<Point3<f32>>>> {
-    [
+/// The teapot's patches.
+pub fn control_points() -> PatchSet {
+    PatchSet::new(vec![
         // 0
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(1.4, 0.0, 2.4),
                 Point3::new(1.4, -0.784, 2.4),
                 Point3::new(0.784, -1.4, 2.4),
                 Point3::new(0.0, -1.4, 2.4),
             ],
-            vec![
+            [
                 Point3::new(1.3375, 0.0, 2.53125),
                 Point3::new(1.3375, -0.749, 2.53125),
                 Point3::new(0.749, -1.3375, 2.53125),
                 Point3::new(0.0, -1.3375, 2.53125),
             ],
-            vec![
+            [
                 Point3::new(1.4375, 0.0, 2.53125),
                 Point3::new(1.4375, -0.805, 2.53125),
                 Point3::new(0.805, -1.4375, 2.53125),
                 Point3::new(0.0, -1.4375, 2.53125),
             ],
-            vec![
+            [
                 Point3::new(1.5, 0.0, 2.4),
                 Point3::new(1.5, -0.84, 2.4),
                 Point3::new(0.84, -1.5, 2.4),
                 Point3::new(0.0, -1.5, 2.4),
             ],
-        ],
+        ]),
         // 1
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(0.0, -1.4, 2.4),
                 Point3::new(-0.784, -1.4, 2.4),
                 Point3::new(-1.4, -0.784, 2.4),
                 Point3::new(-1.4, 0.0, 2.4),
             ],
-            vec![
+            [
                 Point3::new(0.0, -1.3375, 2.53125),
                 Point3::new(-0.749, -1.3375, 2.53125),
                 Point3::new(-1.3375, -0.749, 2.53125),
                 Point3::new(-1.3375, 0.0, 2.53125),
             ],
-            vec![
+            [
                 Point3::new(0.0, -1.4375, 2.53125),
                 Point3::new(-0.805, -1.4375, 2.53125),
                 Point3::new(-1.4375, -0.805, 2.53125),
                 Point3::new(-1.4375, 0.0, 2.53125),
             ],
-            vec![
+            [
                 Point3::new(0.0, -1.5, 2.4),
                 Point3::new(-0.84, -1.5, 2.4),
                 Point3::new(-1.5, -0.84, 2.4),
                 Point3::new(-1.5, 0.0, 2.4),
             ],
-        ],
+        ]),
         // 2
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(-1.4, 0.0, 2.4),
                 Point3::new(-1.4, 0.784, 2.4),
                 Point3::new(-0.784, 1.4, 2.4),
                 Point3::new(0.0, 1.4, 2.4),
             ],
-            vec![
+            [
                 Point3::new(-1.3375, 0.0, 2.53125),
                 Point3::new(-1.3375, 0.749, 2.53125),
                 Point3::new(-0.749, 1.3375, 2.53125),
                 Point3::new(0.0, 1.3375, 2.53125),
             ],
-            vec![
+            [
                 Point3::new(-1.4375, 0.0, 2.53125),
                 Point3::new(-1.4375, 0.805, 2.53125),
                 Point3::new(-0.805, 1.4375, 2.53125),
                 Point3::new(0.0, 1.4375, 2.53125),
             ],
-            vec![
+            [
                 Point3::new(-1.5, 0.0, 2.4),
                 Point3::new(-1.5, 0.84, 2.4),
                 Point3::new(-0.84, 1.5, 2.4),
                 Point3::new(0.0, 1.5, 2.4),
             ],
-        ],
+        ]),
         // 3
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(0.0, 1.4, 2.4),
                 Point3::new(0.784, 1.4, 2.4),
                 Point3::new(1.4, 0.784, 2.4),
                 Point3::new(1.4, 0.0, 2.4),
             ],
-            vec![
+            [
                 Point3::new(0.0, 1.3375, 2.53125),
                 Point3::new(0.749, 1.3375, 2.53125),
                 Point3::new(1.3375, 0.749, 2.53125),
                 Point3::new(1.3375, 0.0, 2.53125),
             ],
-            vec![
+            [
                 Point3::new(0.0, 1.4375, 2.53125),
                 Point3::new(0.805, 1.4375, 2.53125),
                 Point3::new(1.4375, 0.805, 2.53125),
                 Point3::new(1.4375, 0.0, 2.53125),
             ],
-            vec![
+            [
                 Point3::new(0.0, 1.5, 2.4),
                 Point3::new(0.84, 1.5, 2.4),
                 Point3::new(1.5, 0.84, 2.4),
                 Point3::new(1.5, 0.0, 2.4),
             ],
-        ],
+        ]),
         // 4
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(1.5, 0.0, 2.4),
                 Point3::new(1.5, -0.84, 2.4),
                 Point3::new(0.84, -1.5, 2.4),
                 Point3::new(0.0, -1.5, 2.4),
             ],
-            vec![
+            [
                 Point3::new(1.75, 0.0, 1.875),
                 Point3::new(1.75, -0.98, 1.875),
                 Point3::new(0.98, -1.75, 1.875),
                 Point3::new(0.0, -1.75, 1.875),
             ],
-            vec![
+            [
                 Point3::new(2.0, 0.0, 1.35),
                 Point3::new(2.0, -1.12, 1.35),
                 Point3::new(1.12, -2.0, 1.35),
                 Point3::new(0.0, -2.0, 1.35),
             ],
-            vec![
+            [
                 Point3::new(2.0, 0.0, 0.9),
                 Point3::new(2.0, -1.12, 0.9),
                 Point3::new(1.12, -2.0, 0.9),
                 Point3::new(0.0, -2.0, 0.9),
             ],
-        ],
+        ]),
         // 5
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(0.0, -1.5, 2.4),
                 Point3::new(-0.84, -1.5, 2.4),
                 Point3::new(-1.5, -0.84, 2.4),
                 Point3::new(-1.5, 0.0, 2.4),
             ],
-            vec![
+            [
                 Point3::new(0.0, -1.75, 1.875),
                 Point3::new(-0.98, -1.75, 1.875),
                 Point3::new(-1.75, -0.98, 1.875),
                 Point3::new(-1.75, 0.0, 1.875),
             ],
-            vec![
+            [
                 Point3::new(0.0, -2.0, 1.35),
                 Point3::new(-1.12, -2.0, 1.35),
                 Point3::new(-2.0, -1.12, 1.35),
                 Point3::new(-2.0, 0.0, 1.35),
             ],
-            vec![
+            [
                 Point3::new(0.0, -2.0, 0.9),
                 Point3::new(-1.12, -2.0, 0.9),
                 Point3::new(-2.0, -1.12, 0.9),
                 Point3::new(-2.0, 0.0, 0.9),
             ],
-        ],
+        ]),
         // 6
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(-1.5, 0.0, 2.4),
                 Point3::new(-1.5, 0.84, 2.4),
                 Point3::new(-0.84, 1.5, 2.4),
                 Point3::new(0.0, 1.5, 2.4),
             ],
-            vec![
+            [
                 Point3::new(-1.75, 0.0, 1.875),
                 Point3::new(-1.75, 0.98, 1.875),
                 Point3::new(-0.98, 1.75, 1.875),
                 Point3::new(0.0, 1.75, 1.875),
             ],
-            vec![
+            [
                 Point3::new(-2.0, 0.0, 1.35),
                 Point3::new(-2.0, 1.12, 1.35),
                 Point3::new(-1.12, 2.0, 1.35),
                 Point3::new(0.0, 2.0, 1.35),
             ],
-            vec![
+            [
                 Point3::new(-2.0, 0.0, 0.9),
                 Point3::new(-2.0, 1.12, 0.9),
                 Point3::new(-1.12, 2.0, 0.9),
                 Point3::new(0.0, 2.0, 0.9),
             ],
-        ],
+        ]),
         // 7
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(0.0, 1.5, 2.4),
                 Point3::new(0.84, 1.5, 2.4),
                 Point3::new(1.5, 0.84, 2.4),
                 Point3::new(1.5, 0.0, 2.4),
             ],
-            vec![
+            [
                 Point3::new(0.0, 1.75, 1.875),
                 Point3::new(0.98, 1.75, 1.875),
                 Point3::new(1.75, 0.98, 1.875),
                 Point3::new(1.75, 0.0, 1.875),
             ],
-            vec![
+            [
                 Point3::new(0.0, 2.0, 1.35),
                 Point3::new(1.12, 2.0, 1.35),
                 Point3::new(2.0, 1.12, 1.35),
                 Point3::new(2.0, 0.0, 1.35),
             ],
-            vec![
+            [
                 Point3::new(0.0, 2.0, 0.9),
                 Point3::new(1.12, 2.0, 0.9),
                 Point3::new(2.0, 1.12, 0.9),
                 Point3::new(2.0, 0.0, 0.9),
             ],
-        ],
+        ]),
         // 8
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(2.0, 0.0, 0.9),
                 Point3::new(2.0, -1.12, 0.9),
                 Point3::new(1.12, -2.0, 0.9),
                 Point3::new(0.0, -2.0, 0.9),
             ],
-            vec![
+            [
                 Point3::new(2.0, 0.0, 0.45),
                 Point3::new(2.0, -1.12, 0.45),
                 Point3::new(1.12, -2.0, 0.45),
                 Point3::new(0.0, -2.0, 0.45),
             ],
-            vec![
+            [
                 Point3::new(1.5, 0.0, 0.225),
                 Point3::new(1.5, -0.84, 0.225),
                 Point3::new(0.84, -1.5, 0.225),
                 Point3::new(0.0, -1.5, 0.225),
             ],
-            vec![
+            [
                 Point3::new(1.5, 0.0, 0.15),
                 Point3::new(1.5, -0.84, 0.15),
                 Point3::new(0.84, -1.5, 0.15),
                 Point3::new(0.0, -1.5, 0.15),
             ],
-        ],
+        ]),
         // 9
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(0.0, -2.0, 0.9),
                 Point3::new(-1.12, -2.0, 0.9),
                 Point3::new(-2.0, -1.12, 0.9),
                 Point3::new(-2.0, 0.0, 0.9),
             ],
-            vec![
+            [
                 Point3::new(0.0, -2.0, 0.45),
                 Point3::new(-1.12, -2.0, 0.45),
                 Point3::new(-2.0, -1.12, 0.45),
                 Point3::new(-2.0, 0.0, 0.45),
             ],
-            vec![
+            [
                 Point3::new(0.0, -1.5, 0.225),
                 Point3::new(-0.84, -1.5, 0.225),
                 Point3::new(-1.5, -0.84, 0.225),
                 Point3::new(-1.5, 0.0, 0.225),
             ],
-            vec![
+            [
                 Point3::new(0.0, -1.5, 0.15),
                 Point3::new(-0.84, -1.5, 0.15),
                 Point3::new(-1.5, -0.84, 0.15),
                 Point3::new(-1.5, 0.0, 0.15),
             ],
-        ],
+        ]),
         // 10
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(-2.0, 0.0, 0.9),
                 Point3::new(-2.0, 1.12, 0.9),
                 Point3::new(-1.12, 2.0, 0.9),
                 Point3::new(0.0, 2.0, 0.9),
             ],
-            vec![
+            [
                 Point3::new(-2.0, 0.0, 0.45),
                 Point3::new(-2.0, 1.12, 0.45),
                 Point3::new(-1.12, 2.0, 0.45),
                 Point3::new(0.0, 2.0, 0.45),
             ],
-            vec![
+            [
                 Point3::new(-1.5, 0.0, 0.225),
                 Point3::new(-1.5, 0.84, 0.225),
                 Point3::new(-0.84, 1.5, 0.225),
                 Point3::new(0.0, 1.5, 0.225),
             ],
-            vec![
+            [
                 Point3::new(-1.5, 0.0, 0.15),
                 Point3::new(-1.5, 0.84, 0.15),
                 Point3::new(-0.84, 1.5, 0.15),
                 Point3::new(0.0, 1.5, 0.15),
             ],
-        ],
+        ]),
         // 11
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(0.0, 2.0, 0.9),
                 Point3::new(1.12, 2.0, 0.9),
                 Point3::new(2.0, 1.12, 0.9),
                 Point3::new(2.0, 0.0, 0.9),
             ],
-            vec![
+            [
                 Point3::new(0.0, 2.0, 0.45),
                 Point3::new(1.12, 2.0, 0.45),
                 Point3::new(2.0, 1.12, 0.45),
                 Point3::new(2.0, 0.0, 0.45),
             ],
-            vec![
+            [
                 Point3::new(0.0, 1.5, 0.225),
                 Point3::new(0.84, 1.5, 0.225),
                 Point3::new(1.5, 0.84, 0.225),
                 Point3::new(1.5, 0.0, 0.225),
             ],
-            vec![
+            [
                 Point3::new(0.0, 1.5, 0.15),
                 Point3::new(0.84, 1.5, 0.15),
                 Point3::new(1.5, 0.84, 0.15),
                 Point3::new(1.5, 0.0, 0.15),
             ],
-        ],
+        ]),
         // 12
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(-1.6, 0.0, 2.025),
                 Point3::new(-1.6, -0.3, 2.025),
                 Point3::new(-1.5, -0.3, 2.25),
                 Point3::new(-1.5, 0.0, 2.25),
             ],
-            vec![
+            [
                 Point3::new(-2.3, 0.0, 2.025),
                 Point3::new(-2.3, -0.3, 2.025),
                 Point3::new(-2.5, -0.3, 2.25),
                 Point3::new(-2.5, 0.0, 2.25),
             ],
-            vec![
+            [
                 Point3::new(-2.7, 0.0, 2.025),
                 Point3::new(-2.7, -0.3, 2.025),
                 Point3::new(-3.0, -0.3, 2.25),
                 Point3::new(-3.0, 0.0, 2.25),
             ],
-            vec![
+            [
                 Point3::new(-2.7, 0.0, 1.8),
                 Point3::new(-2.7, -0.3, 1.8),
                 Point3::new(-3.0, -0.3, 1.8),
                 Point3::new(-3.0, 0.0, 1.8),
             ],
-        ],
+        ]),
         // 13
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(-1.5, 0.0, 2.25),
                 Point3::new(-1.5, 0.3, 2.25),
                 Point3::new(-1.6, 0.3, 2.025),
                 Point3::new(-1.6, 0.0, 2.025),
             ],
-            vec![
+            [
                 Point3::new(-2.5, 0.0, 2.25),
                 Point3::new(-2.5, 0.3, 2.25),
                 Point3::new(-2.3, 0.3, 2.025),
                 Point3::new(-2.3, 0.0, 2.025),
             ],
-            vec![
+            [
                 Point3::new(-3.0, 0.0, 2.25),
                 Point3::new(-3.0, 0.3, 2.25),
                 Point3::new(-2.7, 0.3, 2.025),
                 Point3::new(-2.7, 0.0, 2.025),
             ],
-            vec![
+            [
                 Point3::new(-3.0, 0.0, 1.8),
                 Point3::new(-3.0, 0.3, 1.8),
                 Point3::new(-2.7, 0.3, 1.8),
                 Point3::new(-2.7, 0.0, 1.8),
             ],
-        ],
+        ]),
         // 14
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(-2.7, 0.0, 1.8),
                 Point3::new(-2.7, -0.3, 1.8),
                 Point3::new(-3.0, -0.3, 1.8),
                 Point3::new(-3.0, 0.0, 1.8),
             ],
-            vec![
+            [
                 Point3::new(-2.7, 0.0, 1.575),
                 Point3::new(-2.7, -0.3, 1.575),
                 Point3::new(-3.0, -0.3, 1.35),
                 Point3::new(-3.0, 0.0, 1.35),
             ],
-            vec![
+            [
                 Point3::new(-2.5, 0.0, 1.125),
                 Point3::new(-2.5, -0.3, 1.125),
                 Point3::new(-2.65, -0.3, 0.9375),
                 Point3::new(-2.65, 0.0, 0.9375),
             ],
-            vec![
+            [
                 Point3::new(-2.0, 0.0, 0.9),
                 Point3::new(-2.0, -0.3, 0.9),
                 Point3::new(-1.9, -0.3, 0.6),
                 Point3::new(-1.9, 0.0, 0.6),
             ],
-        ],
+        ]),
         // 15
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(-3.0, 0.0, 1.8),
                 Point3::new(-3.0, 0.3, 1.8),
                 Point3::new(-2.7, 0.3, 1.8),
                 Point3::new(-2.7, 0.0, 1.8),
             ],
-            vec![
+            [
                 Point3::new(-3.0, 0.0, 1.35),
                 Point3::new(-3.0, 0.3, 1.35),
                 Point3::new(-2.7, 0.3, 1.575),
                 Point3::new(-2.7, 0.0, 1.575),
             ],
-            vec![
+            [
                 Point3::new(-2.65, 0.0, 0.9375),
                 Point3::new(-2.65, 0.3, 0.9375),
                 Point3::new(-2.5, 0.3, 1.125),
                 Point3::new(-2.5, 0.0, 1.125),
             ],
-            vec![
+            [
                 Point3::new(-1.9, 0.0, 0.6),
                 Point3::new(-1.9, 0.3, 0.6),
                 Point3::new(-2.0, 0.3, 0.9),
                 Point3::new(-2.0, 0.0, 0.9),
             ],
-        ],
+        ]),
         // 16
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(1.7, 0.0, 1.425),
                 Point3::new(1.7, -0.66, 1.425),
                 Point3::new(1.7, -0.66, 0.6),
                 Point3::new(1.7, 0.0, 0.6),
             ],
-            vec![
+            [
                 Point3::new(2.6, 0.0, 1.425),
                 Point3::new(2.6, -0.66, 1.425),
                 Point3::new(3.1, -0.66, 0.825),
                 Point3::new(3.1, 0.0, 0.825),
             ],
-            vec![
+            [
                 Point3::new(2.3, 0.0, 2.1),
                 Point3::new(2.3, -0.25, 2.1),
                 Point3::new(2.4, -0.25, 2.025),
                 Point3::new(2.4, 0.0, 2.025),
             ],
-            vec![
+            [
                 Point3::new(2.7, 0.0, 2.4),
                 Point3::new(2.7, -0.25, 2.4),
                 Point3::new(3.3, -0.25, 2.4),
                 Point3::new(3.3, 0.0, 2.4),
             ],
-        ],
+        ]),
         // 17
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(1.7, 0.0, 0.6),
                 Point3::new(1.7, 0.66, 0.6),
                 Point3::new(1.7, 0.66, 1.425),
                 Point3::new(1.7, 0.0, 1.425),
             ],
-            vec![
+            [
                 Point3::new(3.1, 0.0, 0.825),
                 Point3::new(3.1, 0.66, 0.825),
                 Point3::new(2.6, 0.66, 1.425),
                 Point3::new(2.6, 0.0, 1.425),
             ],
-            vec![
+            [
                 Point3::new(2.4, 0.0, 2.025),
                 Point3::new(2.4, 0.25, 2.025),
                 Point3::new(2.3, 0.25, 2.1),
                 Point3::new(2.3, 0.0, 2.1),
             ],
-            vec![
+            [
                 Point3::new(3.3, 0.0, 2.4),
                 Point3::new(3.3, 0.25, 2.4),
                 Point3::new(2.7, 0.25, 2.4),
                 Point3::new(2.7, 0.0, 2.4),
             ],
-        ],
+        ]),
         // 18
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(2.7, 0.0, 2.4),
                 Point3::new(2.7, -0.25, 2.4),
                 Point3::new(3.3, -0.25, 2.4),
                 Point3::new(3.3, 0.0, 2.4),
             ],
-            vec![
+            [
                 Point3::new(2.8, 0.0, 2.475),
                 Point3::new(2.8, -0.25, 2.475),
                 Point3::new(3.525, -0.25, 2.49375),
                 Point3::new(3.525, 0.0, 2.49375),
             ],
-            vec![
+            [
                 Point3::new(2.9, 0.0, 2.475),
                 Point3::new(2.9, -0.15, 2.475),
                 Point3::new(3.45, -0.15, 2.5125),
                 Point3::new(3.45, 0.0, 2.5125),
             ],
-            vec![
+            [
                 Point3::new(2.8, 0.0, 2.4),
                 Point3::new(2.8, -0.15, 2.4),
                 Point3::new(3.2, -0.15, 2.4),
                 Point3::new(3.2, 0.0, 2.4),
             ],
-        ],
+        ]),
         // 19
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(3.3, 0.0, 2.4),
                 Point3::new(3.3, 0.25, 2.4),
                 Point3::new(2.7, 0.25, 2.4),
                 Point3::new(2.7, 0.0, 2.4),
             ],
-            vec![
+            [
                 Point3::new(3.525, 0.0, 2.49375),
                 Point3::new(3.525, 0.25, 2.49375),
                 Point3::new(2.8, 0.25, 2.475),
                 Point3::new(2.8, 0.0, 2.475),
             ],
-            vec![
+            [
                 Point3::new(3.45, 0.0, 2.5125),
                 Point3::new(3.45, 0.15, 2.5125),
                 Point3::new(2.9, 0.15, 2.475),
                 Point3::new(2.9, 0.0, 2.475),
             ],
-            vec![
+            [
                 Point3::new(3.2, 0.0, 2.4),
                 Point3::new(3.2, 0.15, 2.4),
                 Point3::new(2.8, 0.15, 2.4),
                 Point3::new(2.8, 0.0, 2.4),
             ],
-        ],
+        ]),
         // 20
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(0.0, 0.0, 3.15),
                 Point3::new(0.0, 0.0, 3.15),
                 Point3::new(0.0, 0.0, 3.15),
                 Point3::new(0.0, 0.0, 3.15),
             ],
-            vec![
+            [
                 Point3::new(0.8, 0.0, 3.15),
                 Point3::new(0.8, -0.45, 3.15),
                 Point3::new(0.45, -0.8, 3.15),
                 Point3::new(0.0, -0.8, 3.15),
             ],
-            vec![
+            [
                 Point3::new(0.0, 0.0, 2.85),
                 Point3::new(0.0, 0.0, 2.85),
                 Point3::new(0.0, 0.0, 2.85),
                 Point3::new(0.0, 0.0, 2.85),
             ],
-            vec![
+            [
                 Point3::new(0.2, 0.0, 2.7),
                 Point3::new(0.2, -0.112, 2.7),
                 Point3::new(0.112, -0.2, 2.7),
                 Point3::new(0.0, -0.2, 2.7),
             ],
-        ],
+        ]),
         // 21
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(0.0, 0.0, 3.15),
                 Point3::new(0.0, 0.0, 3.15),
                 Point3::new(0.0, 0.0, 3.15),
                 Point3::new(0.0, 0.0, 3.15),
             ],
-            vec![
+            [
                 Point3::new(0.0, -0.8, 3.15),
                 Point3::new(-0.45, -0.8, 3.15),
                 Point3::new(-0.8, -0.45, 3.15),
                 Point3::new(-0.8, 0.0, 3.15),
             ],
-            vec![
+            [
                 Point3::new(0.0, 0.0, 2.85),
                 Point3::new(0.0, 0.0, 2.85),
                 Point3::new(0.0, 0.0, 2.85),
                 Point3::new(0.0, 0.0, 2.85),
             ],
-            vec![
+            [
                 Point3::new(0.0, -0.2, 2.7),
                 Point3::new(-0.112, -0.2, 2.7),
                 Point3::new(-0.2, -0.112, 2.7),
                 Point3::new(-0.2, 0.0, 2.7),
             ],
-        ],
+        ]),
         // 22
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(0.0, 0.0, 3.15),
                 Point3::new(0.0, 0.0, 3.15),
                 Point3::new(0.0, 0.0, 3.15),
                 Point3::new(0.0, 0.0, 3.15),
             ],
-            vec![
+            [
                 Point3::new(-0.8, 0.0, 3.15),
                 Point3::new(-0.8, 0.45, 3.15),
                 Point3::new(-0.45, 0.8, 3.15),
                 Point3::new(0.0, 0.8, 3.15),
             ],
-            vec![
+            [
                 Point3::new(0.0, 0.0, 2.85),
                 Point3::new(0.0, 0.0, 2.85),
                 Point3::new(0.0, 0.0, 2.85),
                 Point3::new(0.0, 0.0, 2.85),
             ],
-            vec![
+            [
                 Point3::new(-0.2, 0.0, 2.7),
                 Point3::new(-0.2, 0.112, 2.7),
                 Point3::new(-0.112, 0.2, 2.7),
                 Point3::new(0.0, 0.2, 2.7),
             ],
-        ],
+        ]),
         // 23
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(0.0, 0.0, 3.15),
                 Point3::new(0.0, 0.0, 3.15),
                 Point3::new(0.0, 0.0, 3.15),
                 Point3::new(0.0, 0.0, 3.15),
             ],
-            vec![
+            [
                 Point3::new(0.0, 0.8, 3.15),
                 Point3::new(0.45, 0.8, 3.15),
                 Point3::new(0.8, 0.45, 3.15),
                 Point3::new(0.8, 0.0, 3.15),
             ],
-            vec![
+            [
                 Point3::new(0.0, 0.0, 2.85),
                 Point3::new(0.0, 0.0, 2.85),
                 Point3::new(0.0, 0.0, 2.85),
                 Point3::new(0.0, 0.0, 2.85),
             ],
-            vec![
+            [
                 Point3::new(0.0, 0.2, 2.7),
                 Point3::new(0.112, 0.2, 2.7),
                 Point3::new(0.2, 0.112, 2.7),
                 Point3::new(0.2, 0.0, 2.7),
             ],
-        ],
+        ]),
         // 24
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(0.2, 0.0, 2.7),
                 Point3::new(0.2, -0.112, 2.7),
                 Point3::new(0.112, -0.2, 2.7),
                 Point3::new(0.0, -0.2, 2.7),
             ],
-            vec![
+            [
                 Point3::new(0.4, 0.0, 2.55),
                 Point3::new(0.4, -0.224, 2.55),
                 Point3::new(0.224, -0.4, 2.55),
                 Point3::new(0.0, -0.4, 2.55),
             ],
-            vec![
+            [
                 Point3::new(1.3, 0.0, 2.55),
                 Point3::new(1.3, -0.728, 2.55),
                 Point3::new(0.728, -1.3, 2.55),
                 Point3::new(0.0, -1.3, 2.55),
             ],
-            vec![
+            [
                 Point3::new(1.3, 0.0, 2.4),
                 Point3::new(1.3, -0.728, 2.4),
                 Point3::new(0.728, -1.3, 2.4),
                 Point3::new(0.0, -1.3, 2.4),
             ],
-        ],
+        ]),
         // 25
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(0.0, -0.2, 2.7),
                 Point3::new(-0.112, -0.2, 2.7),
                 Point3::new(-0.2, -0.112, 2.7),
                 Point3::new(-0.2, 0.0, 2.7),
             ],
-            vec![
+            [
                 Point3::new(0.0, -0.4, 2.55),
                 Point3::new(-0.224, -0.4, 2.55),
                 Point3::new(-0.4, -0.224, 2.55),
                 Point3::new(-0.4, 0.0, 2.55),
             ],
-            vec![
+            [
                 Point3::new(0.0, -1.3, 2.55),
                 Point3::new(-0.728, -1.3, 2.55),
                 Point3::new(-1.3, -0.728, 2.55),
                 Point3::new(-1.3, 0.0, 2.55),
             ],
-            vec![
+            [
                 Point3::new(0.0, -1.3, 2.4),
                 Point3::new(-0.728, -1.3, 2.4),
                 Point3::new(-1.3, -0.728, 2.4),
                 Point3::new(-1.3, 0.0, 2.4),
             ],
-        ],
+        ]),
         // 26
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(-0.2, 0.0, 2.7),
                 Point3::new(-0.2, 0.112, 2.7),
                 Point3::new(-0.112, 0.2, 2.7),
                 Point3::new(0.0, 0.2, 2.7),
             ],
-            vec![
+            [
                 Point3::new(-0.4, 0.0, 2.55),
                 Point3::new(-0.4, 0.224, 2.55),
                 Point3::new(-0.224, 0.4, 2.55),
                 Point3::new(0.0, 0.4, 2.55),
             ],
-            vec![
+            [
                 Point3::new(-1.3, 0.0, 2.55),
                 Point3::new(-1.3, 0.728, 2.55),
                 Point3::new(-0.728, 1.3, 2.55),
                 Point3::new(0.0, 1.3, 2.55),
             ],
-            vec![
+            [
                 Point3::new(-1.3, 0.0, 2.4),
                 Point3::new(-1.3, 0.728, 2.4),
                 Point3::new(-0.728, 1.3, 2.4),
                 Point3::new(0.0, 1.3, 2.4),
             ],
-        ],
+        ]),
         // 27
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(0.0, 0.2, 2.7),
                 Point3::new(0.112, 0.2, 2.7),
                 Point3::new(0.2, 0.112, 2.7),
                 Point3::new(0.2, 0.0, 2.7),
             ],
-            vec![
+            [
                 Point3::new(0.0, 0.4, 2.55),
                 Point3::new(0.224, 0.4, 2.55),
                 Point3::new(0.4, 0.224, 2.55),
                 Point3::new(0.4, 0.0, 2.55),
             ],
-            vec![
+            [
                 Point3::new(0.0, 1.3, 2.55),
                 Point3::new(0.728, 1.3, 2.55),
                 Point3::new(1.3, 0.728, 2.55),
                 Point3::new(1.3, 0.0, 2.55),
             ],
-            vec![
+            [
                 Point3::new(0.0, 1.3, 2.4),
                 Point3::new(0.728, 1.3, 2.4),
                 Point3::new(1.3, 0.728, 2.4),
                 Point3::new(1.3, 0.0, 2.4),
             ],
-        ],
+        ]),
         // 28
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(0.0, 0.0, 0.0),
                 Point3::new(0.0, 0.0, 0.0),
                 Point3::new(0.0, 0.0, 0.0),
                 Point3::new(0.0, 0.0, 0.0),
             ],
-            vec![
+            [
                 Point3::new(1.425, 0.0, 0.0),
                 Point3::new(1.425, 0.798, 0.0),
                 Point3::new(0.798, 1.425, 0.0),
                 Point3::new(0.0, 1.425, 0.0),
             ],
-            vec![
+            [
                 Point3::new(1.5, 0.0, 0.075),
                 Point3::new(1.5, 0.84, 0.075),
                 Point3::new(0.84, 1.5, 0.075),
                 Point3::new(0.0, 1.5, 0.075),
             ],
-            vec![
+            [
                 Point3::new(1.5, 0.0, 0.15),
                 Point3::new(1.5, 0.84, 0.15),
                 Point3::new(0.84, 1.5, 0.15),
                 Point3::new(0.0, 1.5, 0.15),
             ],
-        ],
+        ]),
         // 29
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(0.0, 0.0, 0.0),
                 Point3::new(0.0, 0.0, 0.0),
                 Point3::new(0.0, 0.0, 0.0),
                 Point3::new(0.0, 0.0, 0.0),
             ],
-            vec![
+            [
                 Point3::new(0.0, 1.425, 0.0),
                 Point3::new(-0.798, 1.425, 0.0),
                 Point3::new(-1.425, 0.798, 0.0),
                 Point3::new(-1.425, 0.0, 0.0),
             ],
-            vec![
+            [
                 Point3::new(0.0, 1.5, 0.075),
                 Point3::new(-0.84, 1.5, 0.075),
                 Point3::new(-1.5, 0.84, 0.075),
                 Point3::new(-1.5, 0.0, 0.075),
             ],
-            vec![
+            [
                 Point3::new(0.0, 1.5, 0.15),
                 Point3::new(-0.84, 1.5, 0.15),
                 Point3::new(-1.5, 0.84, 0.15),
                 Point3::new(-1.5, 0.0, 0.15),
             ],
-        ],
+        ]),
         // 30
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(0.0, 0.0, 0.0),
                 Point3::new(0.0, 0.0, 0.0),
                 Point3::new(0.0, 0.0, 0.0),
                 Point3::new(0.0, 0.0, 0.0),
             ],
-            vec![
+            [
                 Point3::new(-1.425, 0.0, 0.0),
                 Point3::new(-1.425, -0.798, 0.0),
                 Point3::new(-0.798, -1.425, 0.0),
                 Point3::new(0.0, -1.425, 0.0),
             ],
-            vec![
+            [
                 Point3::new(-1.5, 0.0, 0.075),
                 Point3::new(-1.5, -0.84, 0.075),
                 Point3::new(-0.84, -1.5, 0.075),
                 Point3::new(0.0, -1.5, 0.075),
             ],
-            vec![
+            [
                 Point3::new(-1.5, 0.0, 0.15),
                 Point3::new(-1.5, -0.84, 0.15),
                 Point3::new(-0.84, -1.5, 0.15),
                 Point3::new(0.0, -1.5, 0.15),
             ],
-        ],
+        ]),
         // 31
-        vec![
-            vec![
+        BezierPatch::new([
+            [
                 Point3::new(0.0, 0.0, 0.0),
                 Point3::new(0.0, 0.0, 0.0),
                 Point3::new(0.0, 0.0, 0.0),
                 Point3::new(0.0, 0.0, 0.0),
             ],
-            vec![
+            [
                 Point3::new(0.0, -1.425, 0.0),
                 Point3::new(0.798, -1.425, 0.0),
                 Point3::new(1.425, -0.798, 0.0),
                 Point3::new(1.425, 0.0, 0.0),
             ],
-            vec![
+            [
                 Point3::new(0.0, -1.5, 0.075),
                 Point3::new(0.84, -1.5, 0.075),
                 Point3::new(1.5, -0.84, 0.075),
                 Point3::new(1.5, 0.0, 0.075),
             ],
-            vec![
+            [
                 Point3::new(0.0, -1.5, 0.15),
                 Point3::new(0.84, -1.5, 0.15),
                 Point3::new(1.5, -0.84, 0.15),
                 Point3::new(1.5, 0.0, 0.15),
             ],
-        ],
-    ]
-    .to_vec()
+        ]),
+    ])
 }
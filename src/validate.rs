@@ -0,0 +1,226 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use crate::mesh::Mesh;
+use crate::weld::PointGrid;
+use cgmath::*;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Controls what [`Mesh::validate`] counts as a problem.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ValidateOptions {
+    /// Vertices closer than this are the same point, so the copies of a
+    /// seam that an unwelded mesh has on each side of it join up. A
+    /// triangle whose corners all lie within this distance of a line is
+    /// degenerate.
+    pub tolerance: f32,
+}
+
+impl Default for ValidateOptions {
+    fn default() -> ValidateOptions {
+        ValidateOptions { tolerance: 1e-5 }
+    }
+}
+
+/// What [`Mesh::validate`] found wrong with a mesh. Triangles are numbered
+/// in the order they appear in [`Mesh::indices`], and edges and loops are
+/// given by the first vertex at each of their points.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValidationReport {
+    /// Triangles that refer to vertices the mesh doesn't have. These are
+    /// left out of every other check.
+    pub invalid_triangles: Vec<usize>,
+    /// Vertices with a NaN or infinite position, normal, uv or tangent.
+    pub non_finite_vertices: Vec<usize>,
+    /// Triangles with no area, such as a row of them along a side of a patch
+    /// that has collapsed to a point, where the teapot's tessellations make
+    /// a fan instead. These have no direction, so they're left out of the
+    /// edge and normal checks.
+    pub degenerate_triangles: Vec<usize>,
+    /// Edges shared by more than two triangles.
+    pub non_manifold_edges: Vec<[u32; 2]>,
+    /// Chains of edges with a triangle on only one side, in the direction
+    /// the triangles wind along them. These are closed loops unless they
+    /// run into a non-manifold edge.
+    pub boundary_loops: Vec<Vec<u32>>,
+    /// Triangles whose winding points them away from their vertex normals.
+    pub flipped_triangles: Vec<usize>,
+}
+
+impl ValidationReport {
+    /// True if nothing is wrong with the mesh, other than it having
+    /// boundaries, which many meshes are meant to have.
+    pub fn is_valid(&self) -> bool {
+        self.invalid_triangles.is_empty()
+            && self.non_finite_vertices.is_empty()
+            && self.degenerate_triangles.is_empty()
+            && self.non_manifold_edges.is_empty()
+            && self.flipped_triangles.is_empty()
+    }
+
+    /// True if the mesh has no boundaries, so it encloses a volume.
+    pub fn is_closed(&self) -> bool {
+        self.boundary_loops.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "invalid triangles: {}", self.invalid_triangles.len())?;
+        writeln!(f, "non-finite vertices: {}", self.non_finite_vertices.len())?;
+        writeln!(
+            f,
+            "degenerate triangles: {}",
+            self.degenerate_triangles.len()
+        )?;
+        writeln!(f, "non-manifold edges: {}", self.non_manifold_edges.len())?;
+        write!(f, "boundary loops: {}", self.boundary_loops.len())?;
+        for boundary in self.boundary_loops.iter() {
+            write!(f, "\n  {} edges", boundary.len())?;
+        }
+        write!(f, "\nflipped triangles: {}", self.flipped_triangles.len())
+    }
+}
+
+impl Mesh {
+    /// Checks the mesh for problems that trip up renderers and tools
+    /// further down an asset pipeline.
+    ///
+    /// Vertices at the same point are matched up first, so the duplicated
+    /// seams of an unwelded mesh aren't reported as boundaries.
+    ///
+    /// ```
+    /// let mesh = teapot::create_vertices(8, 8);
+    /// let report = mesh.validate(&teapot::ValidateOptions::default());
+    /// assert!(report.non_finite_vertices.is_empty());
+    /// assert!(report.flipped_triangles.is_empty());
+    /// ```
+    pub fn validate(&self, options: &ValidateOptions) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        let finite = |v: &[f32]| v.iter().all(|x| x.is_finite());
+        for i in 0..self.vertices.len() {
+            let ok = finite(&self.vertices[i])
+                && self.normals.get(i).iter().all(|n| finite(*n))
+                && self.uvs.get(i).iter().all(|t| finite(*t))
+                && self.tangents.get(i).iter().all(|t| finite(*t));
+            if !ok {
+                report.non_finite_vertices.push(i);
+            }
+        }
+
+        let points = self.point_ids(options.tolerance);
+        let position = |i: u32| {
+            let v = self.vertices[i as usize];
+            Vector3::new(v[0], v[1], v[2])
+        };
+
+        // each directed edge of the triangles that are left
+        let mut edges: Vec<(u32, u32)> = Vec::new();
+        for (t, tri) in self.indices.chunks(3).enumerate() {
+            if tri.len() < 3 || tri.iter().any(|&i| i as usize >= self.vertices.len()) {
+                report.invalid_triangles.push(t);
+                continue;
+            }
+            if !finite(&self.vertices[tri[0] as usize])
+                || !finite(&self.vertices[tri[1] as usize])
+                || !finite(&self.vertices[tri[2] as usize])
+            {
+                continue;
+            }
+
+            // the smallest height of a triangle is twice its area over its
+            // longest side
+            let (a, b, c) = (position(tri[0]), position(tri[1]), position(tri[2]));
+            let area2 = (b - a).cross(c - a).magnitude();
+            let longest = (b - a)
+                .magnitude()
+                .max((c - b).magnitude())
+                .max((a - c).magnitude());
+            let collapsed = {
+                let p = |k: usize| points[tri[k] as usize];
+                p(0) == p(1) || p(1) == p(2) || p(2) == p(0)
+            };
+            if collapsed || area2 <= options.tolerance * longest {
+                report.degenerate_triangles.push(t);
+                continue;
+            }
+
+            let normal = tri
+                .iter()
+                .filter_map(|&i| self.normals.get(i as usize))
+                .fold(Vector3::zero(), |sum, &n| sum + Vector3::from(n));
+            if (b - a).cross(c - a).dot(normal) < 0.0 {
+                report.flipped_triangles.push(t);
+            }
+
+            for k in 0..3 {
+                edges.push((points[tri[k] as usize], points[tri[(k + 1) % 3] as usize]));
+            }
+        }
+
+        let mut uses: HashMap<(u32, u32), usize> = HashMap::new();
+        for &(a, b) in edges.iter() {
+            *uses.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+        }
+        report.non_manifold_edges = uses
+            .iter()
+            .filter(|&(_, &n)| n > 2)
+            .map(|(&(a, b), _)| [a, b])
+            .collect();
+        report.non_manifold_edges.sort();
+
+        // follow the edges used only once from end to start
+        let boundary: Vec<(u32, u32)> = edges
+            .into_iter()
+            .filter(|&(a, b)| uses[&(a.min(b), a.max(b))] == 1)
+            .collect();
+        let mut leaving: HashMap<u32, Vec<usize>> = HashMap::new();
+        for (e, &(a, _)) in boundary.iter().enumerate() {
+            leaving.entry(a).or_default().push(e);
+        }
+        let mut used = vec![false; boundary.len()];
+        for e in 0..boundary.len() {
+            if used[e] {
+                continue;
+            }
+            used[e] = true;
+            let (start, mut end) = boundary[e];
+            let mut chain = vec![start];
+            while end != start {
+                let next = leaving
+                    .get(&end)
+                    .into_iter()
+                    .flatten()
+                    .cloned()
+                    .find(|&n| !used[n]);
+                chain.push(end);
+                match next {
+                    Some(n) => {
+                        used[n] = true;
+                        end = boundary[n].1;
+                    }
+                    None => break,
+                }
+            }
+            report.boundary_loops.push(chain);
+        }
+
+        report
+    }
+
+    // For each vertex, the first vertex within `tolerance` of it, by the
+    // same test as weld
+    fn point_ids(&self, tolerance: f32) -> Vec<u32> {
+        let mut grid = PointGrid::new(tolerance);
+        let mut ids = Vec::with_capacity(self.vertices.len());
+        for (i, v) in self.vertices.iter().enumerate() {
+            let p = Vector3::new(v[0], v[1], v[2]);
+            ids.push(grid.find(p, |_| true).unwrap_or_else(|| {
+                grid.insert(p, i as u32);
+                i as u32
+            }));
+        }
+        ids
+    }
+}
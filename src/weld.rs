@@ -0,0 +1,175 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use crate::mesh::Mesh;
+use cgmath::*;
+use std::collections::HashMap;
+
+/// Controls how [`Mesh::weld`] merges vertices.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WeldOptions {
+    /// Vertices closer than this are candidates for merging.
+    pub tolerance: f32,
+    /// Coincident vertices whose normals differ by more than this are kept
+    /// apart, so the mesh stays sharp along that crease. Anything at or
+    /// above 180 degrees merges every coincident vertex.
+    pub crease_angle: Deg<f32>,
+}
+
+impl Default for WeldOptions {
+    fn default() -> WeldOptions {
+        WeldOptions {
+            tolerance: 1e-5,
+            crease_angle: Deg(180.0),
+        }
+    }
+}
+
+// Points bucketed into cubes as big as the tolerance, so any point within
+// the tolerance of another is in the same cube or one of the 26 around it.
+// Mesh::weld and Mesh::validate both use it, so they agree about which
+// points coincide.
+pub(crate) struct PointGrid {
+    tolerance2: f32,
+    cell: f32,
+    cells: HashMap<[i64; 3], Vec<(u32, Vector3<f32>)>>,
+}
+
+impl PointGrid {
+    pub(crate) fn new(tolerance: f32) -> PointGrid {
+        PointGrid {
+            tolerance2: tolerance * tolerance,
+            cell: if tolerance > 0.0 { tolerance } else { 1.0 },
+            cells: HashMap::new(),
+        }
+    }
+
+    fn key(&self, p: Vector3<f32>) -> [i64; 3] {
+        [
+            (p.x / self.cell).floor() as i64,
+            (p.y / self.cell).floor() as i64,
+            (p.z / self.cell).floor() as i64,
+        ]
+    }
+
+    // The first point added within the tolerance of `p` that `accept` takes
+    pub(crate) fn find<F: FnMut(u32) -> bool>(
+        &self,
+        p: Vector3<f32>,
+        mut accept: F,
+    ) -> Option<u32> {
+        let key = self.key(p);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let k = [key[0] + dx, key[1] + dy, key[2] + dz];
+                    for &(id, q) in self.cells.get(&k).into_iter().flatten() {
+                        if (p - q).magnitude2() <= self.tolerance2 && accept(id) {
+                            return Some(id);
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    pub(crate) fn insert(&mut self, p: Vector3<f32>, id: u32) {
+        let key = self.key(p);
+        self.cells.entry(key).or_default().push((id, p));
+    }
+}
+
+impl Mesh {
+    /// Merges vertices that share a position, such as the copies of a seam
+    /// that [`create_vertices`](crate::create_vertices) makes for each of
+    /// the patches on either side of it, and remaps the indices to match.
+    ///
+    /// Merged vertices get the average of their normals and keep the uv and
+    /// tangent of the first of them. Triangles that collapse to a line or a
+    /// point once their corners are merged, such as those with an edge
+    /// shorter than the tolerance, are removed.
+    pub fn weld(&self, options: &WeldOptions) -> Mesh {
+        let min_cos = if options.crease_angle >= Deg(180.0) {
+            -1.0
+        } else {
+            options.crease_angle.cos()
+        };
+
+        let mut welded = Mesh::with_capacity(self.vertices.len(), self.indices.len());
+        let mut normal_sums: Vec<Vector3<f32>> = Vec::with_capacity(self.vertices.len());
+        let mut grid = PointGrid::new(options.tolerance);
+        let mut remap: Vec<u32> = Vec::with_capacity(self.vertices.len());
+
+        for i in 0..self.vertices.len() {
+            let p = Vector3::new(self.vertices[i][0], self.vertices[i][1], self.vertices[i][2]);
+            let n = Vector3::from(self.normals[i]);
+
+            // look for a vertex we already have with a close enough normal
+            let normals = &welded.normals;
+            let found = grid.find(p, |j| n.dot(Vector3::from(normals[j as usize])) >= min_cos);
+
+            let j = match found {
+                Some(j) => {
+                    normal_sums[j as usize] += n;
+                    j
+                }
+                None => {
+                    let j = welded.vertices.len() as u32;
+                    welded.vertices.push(self.vertices[i]);
+                    welded.normals.push(self.normals[i]);
+                    welded.uvs.push(self.uvs[i]);
+                    if let Some(&t) = self.tangents.get(i) {
+                        welded.tangents.push(t);
+                    }
+                    normal_sums.push(n);
+                    grid.insert(p, j);
+                    j
+                }
+            };
+            remap.push(j);
+        }
+
+        for (normal, sum) in welded.normals.iter_mut().zip(normal_sums) {
+            if sum.magnitude2() > 0.0 {
+                *normal = sum.normalize().into();
+            }
+        }
+
+        // keep the tangents perpendicular to the averaged normals
+        if self.has_tangents() {
+            for (tangent, normal) in welded.tangents.iter_mut().zip(welded.normals.iter()) {
+                let n = Vector3::from(*normal);
+                let t = Vector3::new(tangent[0], tangent[1], tangent[2]);
+                let t = t - n * n.dot(t);
+                if t.magnitude2() > 0.0 {
+                    *tangent = t.normalize().extend(tangent[3]).into();
+                }
+            }
+        } else {
+            welded.tangents.clear();
+        }
+
+        let keep = |tri: &[u32], indices: &mut Vec<u32>| {
+            let a = remap[tri[0] as usize];
+            let b = remap[tri[1] as usize];
+            let c = remap[tri[2] as usize];
+            if a != b && b != c && c != a {
+                indices.extend_from_slice(&[a, b, c]);
+            }
+        };
+        if self.patch_ranges.is_empty() {
+            for tri in self.indices.chunks(3) {
+                keep(tri, &mut welded.indices);
+            }
+        }
+        for range in self.patch_ranges.iter() {
+            let start = welded.indices.len();
+            for tri in self.indices[range.clone()].chunks(3) {
+                keep(tri, &mut welded.indices);
+            }
+            welded.patch_ranges.push(start..welded.indices.len());
+        }
+
+        welded
+    }
+}
@@ -0,0 +1,154 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use cgmath::*;
+use teapot::BezierPatch;
+
+// Distance from `p` to the nearest point of `patch`, by Gauss-Newton from
+// `(u, v)`
+fn distance_to_patch(patch: &BezierPatch, p: Vector3<f64>, (mut u, mut v): (f64, f64)) -> f64 {
+    for _ in 0..10 {
+        let s = patch.evaluate(u, v);
+        let r = p - s.position.to_vec();
+        let (a, b, c) = (s.du.dot(s.du), s.du.dot(s.dv), s.dv.dot(s.dv));
+        let det = a * c - b * b;
+        if det.abs() < 1e-12 {
+            break;
+        }
+        let (x, y) = (s.du.dot(r), s.dv.dot(r));
+        u = (u + (c * x - b * y) / det).clamp(0.0, 1.0);
+        v = (v + (a * y - b * x) / det).clamp(0.0, 1.0);
+    }
+    (p - patch.evaluate(u, v).position.to_vec()).magnitude()
+}
+
+#[test]
+fn triangles_are_within_tolerance() {
+    let patches = teapot::control_points();
+    for &tolerance in &[0.1, 0.01] {
+        let mesh = teapot::tesselate_adaptive(&patches, tolerance);
+        for (i, patch) in patches.iter().enumerate() {
+            for t in mesh.patch_indices(i).chunks(3) {
+                // the centre and the middle of each edge
+                let weights = [
+                    [1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0],
+                    [0.5, 0.5, 0.0],
+                    [0.0, 0.5, 0.5],
+                    [0.5, 0.0, 0.5],
+                ];
+                for w in weights.iter() {
+                    let mut p = Vector3::zero();
+                    let mut uv = (0.0, 0.0);
+                    for (&k, &w) in t.iter().zip(w) {
+                        let [x, y, z, _] = mesh.vertices[k as usize];
+                        p += Vector3::new(x, y, z).cast::<f64>().unwrap() * w;
+                        let [u, v] = mesh.uvs[k as usize];
+                        uv.0 += f64::from(u) * w;
+                        uv.1 += f64::from(v) * w;
+                    }
+                    let d = distance_to_patch(patch, p, uv);
+                    assert!(
+                        d <= f64::from(tolerance),
+                        "patch {} is {} from the surface at tolerance {}",
+                        i,
+                        d,
+                        tolerance
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn resolution_grows_as_tolerance_shrinks() {
+    let patches = teapot::control_points();
+    for (i, patch) in patches.iter().enumerate() {
+        let mut last = (0, 0);
+        for &tolerance in &[1.0, 0.1, 0.01, 0.001] {
+            let (nr, nc) = teapot::patch_resolution(patch, tolerance);
+            assert!(nr >= last.0 && nc >= last.1, "patch {}", i);
+            assert!(nr * nc > last.0 * last.1, "patch {}", i);
+            last = (nr, nc);
+        }
+    }
+}
+
+#[test]
+fn resolution_follows_curvature() {
+    // the lid knob is smaller round than the body below the rim, but
+    // curves tightly at its neck, so it gets fewer columns but more rows
+    let patches = teapot::control_points();
+    for &tolerance in &[0.01, 0.001] {
+        let res = |i: usize| teapot::patch_resolution(&patches[i], tolerance);
+        for knob in 20..24 {
+            for body in 4..12 {
+                let ((kr, kc), (br, bc)) = (res(knob), res(body));
+                assert!(
+                    kc < bc && kr > br,
+                    "knob {} is {}x{}, body {} is {}x{}",
+                    knob,
+                    kr,
+                    kc,
+                    body,
+                    br,
+                    bc
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn triangle_count_is_exact() {
+    let patches = teapot::control_points();
+    for &tolerance in &[0.5, 0.1, 0.01] {
+        let mesh = teapot::tesselate_adaptive(&patches, tolerance);
+        assert_eq!(
+            teapot::adaptive_triangle_count(&patches, tolerance),
+            mesh.num_triangles(),
+            "tolerance {}",
+            tolerance
+        );
+    }
+}
+
+#[test]
+fn budget_is_kept() {
+    let patches = teapot::control_points();
+    for &budget in &[1000, 5000, 20000] {
+        let tolerance = teapot::tolerance_for_budget(&patches, budget);
+        let triangles = teapot::tesselate_adaptive(&patches, tolerance).num_triangles();
+        assert!(
+            triangles <= budget,
+            "{} triangles for {}",
+            triangles,
+            budget
+        );
+        // and not far under it
+        assert!(
+            triangles > budget * 9 / 10,
+            "{} triangles for {}",
+            triangles,
+            budget
+        );
+    }
+}
+
+#[test]
+fn budget_out_of_reach() {
+    let patches = teapot::control_points();
+    // one quad per patch is the best there is
+    let tolerance = teapot::tolerance_for_budget(&patches, 10);
+    assert!(patches
+        .iter()
+        .all(|p| teapot::patch_resolution(p, tolerance) == (2, 2)));
+    assert_eq!(teapot::tolerance_for_budget(&patches, 0), tolerance);
+
+    // and every patch at the most rows and columns the finest
+    let tolerance = teapot::tolerance_for_budget(&patches, usize::MAX);
+    assert!(tolerance > 0.0);
+    let finest = (teapot::MAX_RESOLUTION, teapot::MAX_RESOLUTION);
+    assert!(patches
+        .iter()
+        .all(|p| teapot::patch_resolution(p, tolerance) == finest));
+}
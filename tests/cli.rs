@@ -0,0 +1,185 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use std::path::PathBuf;
+use teapot::cli::{self, Options};
+
+fn parse(args: &[&str]) -> Result<Options, String> {
+    cli::parse_args(args.iter().map(|a| a.to_string()))
+}
+
+// A directory of its own for each test's files
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("teapot-cli-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn parse_viewer_options() {
+    let options = parse(&[]).unwrap();
+    assert_eq!(options, Options::default());
+
+    let options = parse(&[
+        "--model",
+        "cup.bpt",
+        "--tolerance",
+        "0.01",
+        "--export",
+        "cup.stl",
+        "--ascii",
+        "--shading",
+        "phong",
+    ])
+    .unwrap();
+    assert_eq!(options.model.as_deref(), Some("cup.bpt"));
+    assert_eq!(options.tolerance, Some(0.01));
+    assert_eq!(options.export.as_deref(), Some("cup.stl"));
+    assert!(options.ascii);
+    assert_eq!(options.shading, Some(teapot::Shading::Phong));
+    assert!(!options.render && !options.validate && options.turntable.is_none());
+}
+
+#[test]
+fn parse_render_options() {
+    let options = parse(&[
+        "render", "--width", "320", "--height", "200", "--time", "1.5", "-o", "a.ppm",
+    ])
+    .unwrap();
+    assert!(options.render);
+    assert_eq!(options.size(), (320, 200));
+    assert_eq!(options.time, 1.5);
+    assert_eq!(options.output.as_deref(), Some("a.ppm"));
+
+    let options = parse(&["render", "--output", "a.png"]).unwrap();
+    assert_eq!(options.size(), (800, 600));
+    assert_eq!(options.time, 0.0);
+
+    let options = parse(&["turntable", "--frames", "12"]).unwrap();
+    assert_eq!(options.turntable, Some(12));
+    assert_eq!(options.output, None);
+    assert_eq!(parse(&["turntable"]).unwrap().turntable, Some(36));
+    assert!(parse(&["validate"]).unwrap().validate);
+}
+
+#[test]
+fn parse_errors() {
+    let error = |args: &[&str]| parse(args).unwrap_err();
+    assert_eq!(
+        error(&["render"]),
+        "render needs an output file, -o <file.png|.ppm>"
+    );
+    assert_eq!(error(&["render", "-o"]), "--output needs a file name");
+    assert_eq!(error(&["--width", "0"]), "invalid width '0'");
+    assert_eq!(error(&["--height", "tall"]), "invalid height 'tall'");
+    assert_eq!(error(&["--height"]), "--height needs a number of pixels");
+    assert_eq!(error(&["--time", "inf"]), "invalid time 'inf'");
+    assert_eq!(error(&["--tolerance", "-1"]), "invalid tolerance '-1'");
+    assert_eq!(error(&["--model"]), "--model needs a file name");
+    assert_eq!(
+        error(&["--model", "bunny.OBJ", "--tolerance", "0.1"]),
+        "--tolerance only applies to patch models, not OBJ meshes"
+    );
+    assert_eq!(
+        error(&["turntable", "--frames", "0"]),
+        "invalid number of frames '0'"
+    );
+    // --frames is only for turntables, and subcommands only come first
+    assert_eq!(error(&["--frames", "3"]), "unknown argument '--frames'");
+    assert_eq!(error(&["--ascii", "render"]), "unknown argument 'render'");
+}
+
+#[test]
+fn load_mesh_errors() {
+    let options = parse(&["--model", "no such file.bpt"]).unwrap();
+    assert!(cli::load_mesh(&options)
+        .unwrap_err()
+        .starts_with("no such file.bpt: "));
+
+    let mesh = cli::load_mesh(&Options::default()).unwrap();
+    assert_eq!(mesh.num_vertices(), 32 * 18 * 23 - 8 * (23 - 1));
+}
+
+#[test]
+fn export_by_extension() {
+    let dir = temp_dir("export");
+    let mesh = teapot::create_vertices(4, 4);
+    let material = teapot::Material::default();
+    for ext in &["obj", "stl", "ply", "glb", "STL"] {
+        let path = dir.join(format!("teapot.{}", ext));
+        let options = parse(&["--export", path.to_str().unwrap()]).unwrap();
+        cli::export_mesh(&options, &mesh, &material).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() > 0, "{}", ext);
+    }
+    let options = parse(&["--export", "teapot.3ds"]).unwrap();
+    assert_eq!(
+        cli::export_mesh(&options, &mesh, &material).unwrap_err(),
+        "teapot.3ds: don't know how to write '.3ds' files"
+    );
+
+    // STL is binary unless asked for text
+    let binary = std::fs::read(dir.join("teapot.stl")).unwrap();
+    assert!(!binary.starts_with(b"solid"));
+    let path = dir.join("ascii.stl");
+    let options = parse(&["--export", path.to_str().unwrap(), "--ascii"]).unwrap();
+    cli::export_mesh(&options, &mesh, &material).unwrap();
+    assert!(std::fs::read(&path).unwrap().starts_with(b"solid"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn render_and_turntable() {
+    let dir = temp_dir("render");
+    let mesh = teapot::create_vertices(6, 6);
+    let (vertices, indices) = (mesh.interleave(), mesh.indices);
+    let lighting = teapot::Lighting::default();
+
+    let path = dir.join("frame.ppm");
+    let options = parse(&[
+        "render",
+        "--width",
+        "40",
+        "--height",
+        "30",
+        "-o",
+        path.to_str().unwrap(),
+    ])
+    .unwrap();
+    cli::save_render(&options, &vertices, &indices, &lighting).unwrap();
+    let image = teapot::load_ppm(&path).unwrap();
+    assert_eq!((image.width(), image.height()), (40, 30));
+    assert_eq!(
+        image,
+        cli::render_frame(&options, &vertices, &indices, &lighting)
+    );
+
+    let options = parse(&["render", "-o", dir.join("frame.jpg").to_str().unwrap()]).unwrap();
+    assert!(cli::save_render(&options, &vertices, &indices, &lighting).is_err());
+
+    let frames = dir.join("frames");
+    let options = parse(&[
+        "turntable",
+        "--frames",
+        "3",
+        "--width",
+        "16",
+        "--height",
+        "16",
+        "-o",
+        frames.to_str().unwrap(),
+    ])
+    .unwrap();
+    assert_eq!(
+        cli::save_turntable(&options, &vertices, &indices, &lighting).unwrap(),
+        frames
+    );
+    let mut names = std::fs::read_dir(&frames)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(
+        names,
+        ["frame_0000.png", "frame_0001.png", "frame_0002.png"]
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,122 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    let mut b = [0u8; 4];
+    b.copy_from_slice(&bytes[offset..offset + 4]);
+    u32::from_le_bytes(b)
+}
+
+#[test]
+fn glb_layout() {
+    let mesh = teapot::create_vertices(6, 8);
+    let mut glb = Vec::new();
+    let material = teapot::Material::default();
+    teapot::write_glb(&mesh, &teapot::PATCH_GROUPS, &material, &mut glb).unwrap();
+
+    assert_eq!(&glb[0..4], b"glTF");
+    assert_eq!(read_u32(&glb, 4), 2);
+    assert_eq!(read_u32(&glb, 8) as usize, glb.len());
+
+    let json_len = read_u32(&glb, 12) as usize;
+    assert_eq!(&glb[16..20], b"JSON");
+    assert_eq!(json_len % 4, 0);
+    let json = std::str::from_utf8(&glb[20..20 + json_len]).unwrap();
+
+    let bin = 20 + json_len;
+    assert_eq!(&glb[bin + 4..bin + 8], b"BIN\0");
+    let bin_len = read_u32(&glb, bin) as usize;
+    assert_eq!(bin_len, 32 * mesh.num_vertices() + 4 * mesh.indices.len());
+    assert_eq!(bin + 8 + bin_len, glb.len());
+
+    // one primitive per group, covering all the triangles between them
+    for (name, _) in teapot::PATCH_GROUPS.iter() {
+        assert!(json.contains(&format!(r#""extras":{{"name":"{}"}}"#, name)));
+    }
+    assert_eq!(json.matches(r#""indices":"#).count(), 5);
+    let counts: usize = json
+        .match_indices(r#""type":"SCALAR""#)
+        .map(|(end, _)| {
+            let start = json[..end].rfind(r#""count":"#).unwrap() + 8;
+            json[start..end]
+                .trim_end_matches(',')
+                .parse::<usize>()
+                .unwrap()
+        })
+        .sum();
+    assert_eq!(counts, mesh.indices.len());
+
+    assert!(json.contains(r#""baseColorFactor":[0.5,0.25,1,1]"#));
+    assert!(json.contains(&format!(r#""specularFactor":{}"#, material.specular)));
+}
+
+#[test]
+fn glb_material() {
+    let mesh = teapot::create_vertices(4, 4);
+    let material = teapot::Material {
+        color: [0.25, 0.5, 0.75],
+        specular: 0.5,
+        shininess: 6.0,
+        ..teapot::Material::default()
+    };
+    let mut glb = Vec::new();
+    teapot::write_glb(&mesh, &[("all", 0..32)], &material, &mut glb).unwrap();
+    let json_len = read_u32(&glb, 12) as usize;
+    let json = std::str::from_utf8(&glb[20..20 + json_len]).unwrap();
+
+    assert!(json.contains(r#""baseColorFactor":[0.25,0.5,0.75,1]"#));
+    assert!(json.contains(r#""specularFactor":0.5"#));
+    // alpha = sqrt(2 / (6 + 2)) = 0.5
+    assert!(json.contains(&format!(r#""roughnessFactor":{}"#, 0.5f32.sqrt())));
+}
+
+fn glb_json(
+    mesh: &teapot::Mesh,
+    groups: &[(&str, std::ops::Range<usize>)],
+    material: &teapot::Material,
+) -> String {
+    let mut glb = Vec::new();
+    teapot::write_glb(mesh, groups, material, &mut glb).unwrap();
+    let json_len = read_u32(&glb, 12) as usize;
+    String::from_utf8(glb[20..20 + json_len].to_vec()).unwrap()
+}
+
+#[test]
+fn glb_escapes_names() {
+    let mesh = teapot::create_vertices(4, 4);
+    let material = teapot::Material::default();
+    let json = glb_json(&mesh, &[("a \"quoted\" \\ name\n", 0..32)], &material);
+    assert!(json.contains(r#""extras":{"name":"a \"quoted\" \\ name\u000a"}"#));
+}
+
+#[test]
+fn glb_material_in_range() {
+    let mesh = teapot::create_vertices(4, 4);
+    let material = teapot::Material {
+        specular: 2.5,
+        shininess: -1.0,
+        ..teapot::Material::default()
+    };
+    let json = glb_json(&mesh, &[("all", 0..32)], &material);
+    assert!(json.contains(r#""specularFactor":1"#));
+    assert!(json.contains(r#""roughnessFactor":1"#));
+
+    for material in [
+        teapot::Material {
+            color: [0.5, f32::NAN, 0.5],
+            ..teapot::Material::default()
+        },
+        teapot::Material {
+            specular: f32::INFINITY,
+            ..teapot::Material::default()
+        },
+        teapot::Material {
+            shininess: -3.0,
+            ..teapot::Material::default()
+        },
+    ]
+    .iter()
+    {
+        let err = teapot::write_glb(&mesh, &[("all", 0..32)], material, Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
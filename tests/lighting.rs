@@ -0,0 +1,120 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use teapot::{Light, Lighting, Locals, Material, Shading};
+use zerocopy::AsBytes;
+
+#[test]
+fn display_round_trips() {
+    let lighting = Lighting {
+        material: Material {
+            color: [0.25, 0.5, 0.125],
+            ambient: 0.2,
+            diffuse: 0.6,
+            specular: 1.5,
+            shininess: 80.0,
+        },
+        light: Light {
+            direction: [0.0, -1.0, 2.0],
+            color: [1.0, 0.9, 0.75],
+        },
+        shading: Shading::Phong,
+    };
+    let text = lighting.to_string();
+    assert!(text.contains("material.shininess = 80\n"));
+    assert!(text.contains("shading = phong\n"));
+    assert_eq!(teapot::parse_lighting(&text).unwrap(), lighting);
+}
+
+#[test]
+fn parse_errors_have_line_numbers() {
+    let error = |text: &str| teapot::parse_lighting(text).unwrap_err().to_string();
+    assert_eq!(
+        error("# comment\nmaterial.gloss = 3"),
+        "line 2: unknown setting 'material.gloss'"
+    );
+    assert_eq!(
+        error("light.color = 1 1"),
+        "line 1: light.color needs three numbers"
+    );
+    assert_eq!(
+        error("material.ambient = lots"),
+        "line 1: invalid material.ambient 'lots'"
+    );
+    assert_eq!(
+        error("material.ambient"),
+        "line 1: expected 'material.ambient = value'"
+    );
+    assert_eq!(
+        error("shading = flat"),
+        "line 1: unknown shading 'flat', expected gouraud or phong"
+    );
+    assert_eq!(
+        error("light.direction = 0 0 0"),
+        "light.direction can't be zero"
+    );
+}
+
+// The std140 offsets of the members of `Locals` in shader.vsh
+#[test]
+fn locals_layout() {
+    let mut lighting = Lighting::default();
+    lighting.material.shininess = 7.0;
+    lighting.light.direction = [0.0, 0.0, 2.0];
+    lighting.light.color = [0.5, 0.5, 0.5];
+    let transform = cgmath::Matrix4::from_scale(3.0);
+    let locals = Locals::new(&transform, &lighting);
+
+    let bytes = locals.as_bytes();
+    assert_eq!(bytes.len(), 144);
+    assert_eq!(Locals::SIZE, 144);
+    let float = |offset: usize| {
+        let mut b = [0; 4];
+        b.copy_from_slice(&bytes[offset..offset + 4]);
+        f32::from_le_bytes(b)
+    };
+    assert_eq!(float(0), 3.0);
+    assert_eq!(float(60), 1.0);
+    assert_eq!(float(64), lighting.material.color[0]);
+    assert_eq!(float(76), lighting.material.ambient);
+    assert_eq!(float(88), 7.0);
+    // normalized
+    assert_eq!(float(104), 1.0);
+    assert_eq!(float(112), 0.5);
+    // a parallel projection, looking along the view axis
+    assert_eq!(float(136), 1.0);
+    assert_eq!(float(140), 0.0);
+
+    // the viewer's camera, circling at a distance of 7 and a height of 3.5
+    let transform = teapot::generate_matrix(1.0, 0.0);
+    let bytes = Locals::new(&transform, &lighting).as_bytes().to_vec();
+    let float = |offset: usize| {
+        let mut b = [0; 4];
+        b.copy_from_slice(&bytes[offset..offset + 4]);
+        f32::from_le_bytes(b)
+    };
+    assert!((float(128) - 7.0).abs() < 1e-4);
+    assert!(float(132).abs() < 1e-4);
+    assert!((float(136) - 3.5).abs() < 1e-4);
+    assert_eq!(float(140), 1.0);
+}
+
+#[test]
+fn render_uses_lighting() {
+    let mesh = teapot::create_vertices(8, 8);
+    let vertices = mesh.interleave();
+    let transform = teapot::generate_matrix(4.0 / 3.0, 0.0);
+
+    // a material with no blue shows none, whatever the light
+    let mut target = teapot::RenderTarget::new(64, 48);
+    let mut lighting = Lighting::default();
+    lighting.material.color = [1.0, 0.5, 0.0];
+    lighting.material.specular = 0.0;
+    target.set_lighting(lighting);
+    target.draw_indexed(&vertices, &mesh.indices, &transform);
+    let image = target.to_image();
+    assert_ne!(image.pixel(32, 24), [0, 0, 0]);
+    assert!(image.pixels().iter().all(|p| p[2] == 0));
+
+    let default = teapot::render(&vertices, &mesh.indices, &transform, 64, 48);
+    assert!(default.pixels().iter().any(|p| p[2] > 0));
+}
@@ -0,0 +1,116 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use teapot::PatchSet;
+
+// The teapot in Newell's format, with each patch's control points listed
+// separately
+fn newell_text(patches: &PatchSet) -> String {
+    let mut text = format!("{}\n", patches.len());
+    for i in 0..patches.len() {
+        let indices: Vec<String> = (1..=16).map(|k| (16 * i + k).to_string()).collect();
+        text += &format!("{}\n", indices.join(","));
+    }
+    text += &format!("{}\n", 16 * patches.len());
+    for p in patches.iter().flat_map(|patch| patch.iter()) {
+        text += &format!("{}, {}, {}\n", p.x, p.y, p.z);
+    }
+    text
+}
+
+fn bpt_text(patches: &PatchSet) -> String {
+    let mut text = format!("{}\n", patches.len());
+    for patch in patches.iter() {
+        text += "3 3\n";
+        for p in patch.iter() {
+            text += &format!("{} {} {}\n", p.x, p.y, p.z);
+        }
+    }
+    text
+}
+
+fn newell_error(text: &str) -> String {
+    teapot::parse_newell(text).unwrap_err().to_string()
+}
+
+#[test]
+fn newell_round_trip() {
+    let patches = teapot::control_points();
+    assert_eq!(
+        teapot::parse_newell(&newell_text(&patches)).unwrap(),
+        patches
+    );
+}
+
+#[test]
+fn bpt_round_trip() {
+    let patches = teapot::control_points();
+    assert_eq!(teapot::parse_bpt(&bpt_text(&patches)).unwrap(), patches);
+}
+
+#[test]
+fn bad_counts() {
+    assert_eq!(newell_error("\n  x\n"), "line 2: invalid patch count 'x'");
+    assert_eq!(
+        newell_error("1 2\n"),
+        "line 1: expected patch count, found 2 fields"
+    );
+
+    // a count too big to allocate is just a file that ends early
+    assert_eq!(
+        newell_error("100000000000000\n"),
+        "unexpected end of file reading patch"
+    );
+    let err = teapot::parse_bpt("100000000000000\n").unwrap_err();
+    assert_eq!(err.to_string(), "unexpected end of file reading patch");
+
+    let text = newell_text(&teapot::control_points()).replacen("\n512\n", "\n-3\n", 1);
+    assert_eq!(newell_error(&text), "line 34: invalid vertex count '-3'");
+}
+
+#[test]
+fn bad_index() {
+    let text = newell_text(&teapot::control_points()).replacen(",16\n", ",513\n", 1);
+    assert_eq!(
+        newell_error(&text),
+        "line 2: vertex index 513 out of range 1..=512"
+    );
+    let text = newell_text(&teapot::control_points()).replacen("1,2,", "0,2,", 1);
+    assert_eq!(
+        newell_error(&text),
+        "line 2: vertex index 0 out of range 1..=512"
+    );
+}
+
+#[test]
+fn truncated_file() {
+    let text = newell_text(&teapot::control_points());
+    let last = text.trim_end().rfind('\n').unwrap();
+    assert_eq!(
+        newell_error(&text[..last]),
+        "unexpected end of file reading vertex"
+    );
+
+    let text = bpt_text(&teapot::control_points());
+    let last = text.trim_end().rfind('\n').unwrap();
+    let err = teapot::parse_bpt(&text[..last]).unwrap_err();
+    assert_eq!(err.to_string(), "unexpected end of file reading vertex");
+}
+
+#[test]
+fn bad_coordinate() {
+    // the first vertex is on the line after the vertex count
+    let text = newell_text(&teapot::control_points());
+    let first = text.lines().nth(34).unwrap();
+    let x = first.split(',').next().unwrap();
+    let text = text.replacen(first, &first.replacen(x, "abc", 1), 1);
+    assert_eq!(newell_error(&text), "line 35: invalid coordinate 'abc'");
+
+    let text = "1\n3 3\n0 0 0\n1 0 zero\n";
+    let err = teapot::parse_bpt(text).unwrap_err();
+    assert_eq!(err.to_string(), "line 4: invalid coordinate 'zero'");
+    let err = teapot::parse_bpt("1\n2 3\n").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "line 2: only bicubic patches are supported, found degree 2 3"
+    );
+}
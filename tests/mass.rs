@@ -0,0 +1,122 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use cgmath::{Matrix3, MetricSpace, Point3, Vector3};
+use teapot::{BezierPatch, MassProperties, PatchSet};
+
+// A box from the origin to `size`, with a flat patch on each face and
+// outward normals
+fn box_patches(size: Vector3<f32>) -> PatchSet {
+    let (x, y, z) = (
+        Vector3::new(size.x, 0.0, 0.0),
+        Vector3::new(0.0, size.y, 0.0),
+        Vector3::new(0.0, 0.0, size.z),
+    );
+    let o = Point3::new(0.0, 0.0, 0.0);
+    // corner, then edges along u and v, with u × v pointing out
+    let faces = [
+        (o, y, x),
+        (o + z, x, y),
+        (o, x, z),
+        (o + y, z, x),
+        (o, z, y),
+        (o + x, y, z),
+    ];
+    let patches = faces.iter().map(|&(corner, u, v)| {
+        let mut points = [[o; 4]; 4];
+        for (j, row) in points.iter_mut().enumerate() {
+            for (i, p) in row.iter_mut().enumerate() {
+                *p = corner + u * (i as f32 / 3.0) + v * (j as f32 / 3.0);
+            }
+        }
+        BezierPatch::new(points)
+    });
+    PatchSet::new(patches.collect())
+}
+
+fn assert_box(props: &MassProperties, size: Vector3<f64>) {
+    let (a, b, c) = (size.x, size.y, size.z);
+    let close = |x: f64, y: f64| (x - y).abs() < 1e-9 * (1.0 + y.abs());
+    assert!(
+        close(props.area, 2.0 * (a * b + b * c + c * a)),
+        "{:?}",
+        props
+    );
+    assert!(close(props.volume, a * b * c), "{:?}", props);
+    assert!(props.centroid.distance(Point3::new(a, b, c) / 2.0) < 1e-9);
+
+    let m = a * b * c;
+    let (ixx, iyy, izz) = (
+        m * (b * b + c * c) / 12.0,
+        m * (c * c + a * a) / 12.0,
+        m * (a * a + b * b) / 12.0,
+    );
+    let expected = Matrix3::new(ixx, 0.0, 0.0, 0.0, iyy, 0.0, 0.0, 0.0, izz);
+    for i in 0..3 {
+        for j in 0..3 {
+            assert!(
+                close(props.inertia[i][j], expected[i][j]),
+                "{:?}",
+                props.inertia
+            );
+        }
+    }
+}
+
+#[test]
+fn box_properties() {
+    let size = Vector3::new(2.0, 3.0, 4.0);
+    let patches = box_patches(size);
+    assert_box(&patches.mass_properties(), size.cast().unwrap());
+
+    // the triangles are the box exactly
+    let mesh = teapot::tesselate_patches(&patches, 2, 2);
+    assert_box(&mesh.mass_properties(), size.cast().unwrap());
+}
+
+#[test]
+fn inertia_is_about_the_centroid() {
+    let size = Vector3::new(1.0, 0.5, 2.0);
+    let moved = box_patches(size).transform(&cgmath::Matrix4::from_translation(Vector3::new(
+        5.0, -3.0, 1.0,
+    )));
+    let props = moved.mass_properties();
+    assert!(props.centroid.distance(Point3::new(5.5, -2.75, 2.0)) < 1e-9);
+    let at_origin = box_patches(size).mass_properties();
+    for i in 0..3 {
+        for j in 0..3 {
+            assert!((props.inertia[i][j] - at_origin.inertia[i][j]).abs() < 1e-9);
+        }
+    }
+}
+
+#[test]
+fn teapot_mesh_converges_to_patches() {
+    let exact = teapot::control_points().mass_properties();
+    let errors: Vec<[f64; 4]> = [8, 16, 32]
+        .iter()
+        .map(|&n| {
+            let mesh = teapot::create_vertices(n, n).mass_properties();
+            let inertia = mesh.inertia - exact.inertia;
+            let inertia = (0..3)
+                .flat_map(|i| (0..3).map(move |j| inertia[i][j].abs()))
+                .fold(0.0, f64::max);
+            [
+                (mesh.area - exact.area).abs() / exact.area,
+                (mesh.volume - exact.volume).abs() / exact.volume,
+                mesh.centroid.distance(exact.centroid),
+                inertia,
+            ]
+        })
+        .collect();
+
+    // errors go down with the square of the spacing, as for any smooth
+    // surface
+    for pair in errors.windows(2) {
+        for (coarse, fine) in pair[0].iter().zip(pair[1].iter()) {
+            let ratio = coarse / fine;
+            assert!(ratio > 3.5 && ratio < 5.0, "{:?}", errors);
+        }
+    }
+    let last = errors[errors.len() - 1];
+    assert!(last[0] < 1e-3 && last[1] < 1e-3, "{:?}", errors);
+}
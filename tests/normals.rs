@@ -0,0 +1,67 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+fn assert_unit_normals(nr: usize, nc: usize) {
+    let mesh = teapot::create_vertices(nr, nc);
+    assert_eq!(mesh.normals.len(), mesh.vertices.len());
+    for (i, n) in mesh.normals.iter().enumerate() {
+        assert!(
+            n.iter().all(|x| x.is_finite()),
+            "normal {} at {:?} is {:?}",
+            i,
+            mesh.vertices[i],
+            n
+        );
+        let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+        assert!(
+            (len - 1.0).abs() < 1e-5,
+            "normal {} at {:?} has length {}",
+            i,
+            mesh.vertices[i],
+            len
+        );
+    }
+}
+
+#[test]
+fn normals_are_finite_and_unit_length() {
+    assert_unit_normals(18, 23);
+    assert_unit_normals(2, 2);
+    assert_unit_normals(3, 7);
+    assert_unit_normals(64, 64);
+}
+
+// The vertices of `mesh` at the point its patch's first row collapses to
+fn pole_normals(patch: &teapot::BezierPatch, mesh: &teapot::Mesh) -> Vec<[f32; 3]> {
+    let pole = patch.points()[0][0];
+    mesh.vertices
+        .iter()
+        .zip(mesh.normals.iter())
+        .filter(|(v, _)| (0..3).all(|k| (v[k] - pole[k]).abs() < 1e-6))
+        .map(|(_, &n)| n)
+        .collect()
+}
+
+#[test]
+fn normals_at_collapsed_rows_point_along_axis() {
+    let patches = teapot::control_points();
+
+    // top of the lid knob, which is a single vertex
+    for i in 20..24 {
+        let mesh = teapot::tesselate_patch(&patches[i], 5, 5);
+        let normals = pole_normals(&patches[i], &mesh);
+        assert_eq!(normals.len(), 1, "patch {}", i);
+        for n in normals.iter() {
+            assert!((n[2] - 1.0).abs() < 1e-5, "patch {}: {:?}", i, n);
+        }
+    }
+
+    // centre of the bottom
+    for i in 28..32 {
+        let mesh = teapot::tesselate_patch(&patches[i], 5, 5);
+        let normals = pole_normals(&patches[i], &mesh);
+        assert_eq!(normals.len(), 1, "patch {}", i);
+        for n in normals.iter() {
+            assert!((n[2] + 1.0).abs() < 1e-5, "patch {}: {:?}", i, n);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+// Reads back what write_obj wrote: positions, texture coordinates,
+// normals, zero-based triangle indices and group names.
+fn read_obj(text: &str) -> teapot::Mesh {
+    let mut mesh = teapot::Mesh::new();
+    let floats = |fields: std::str::SplitWhitespace| -> Vec<f32> {
+        fields.map(|f| f.parse().unwrap()).collect()
+    };
+    for line in text.lines() {
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            Some("v") => {
+                let p = floats(fields);
+                mesh.vertices.push([p[0], p[1], p[2], 1.0]);
+            }
+            Some("vt") => {
+                let t = floats(fields);
+                mesh.uvs.push([t[0], t[1]]);
+            }
+            Some("vn") => {
+                let n = floats(fields);
+                mesh.normals.push([n[0], n[1], n[2]]);
+            }
+            Some("g") => {
+                let start = mesh.indices.len();
+                mesh.patch_ranges.push(start..start);
+            }
+            Some("f") => {
+                for corner in fields {
+                    let refs: Vec<u32> = corner.split('/').map(|i| i.parse().unwrap()).collect();
+                    assert!(refs.iter().all(|&i| i == refs[0]));
+                    mesh.indices.push(refs[0] - 1);
+                }
+                if let Some(range) = mesh.patch_ranges.last_mut() {
+                    range.end = mesh.indices.len();
+                }
+            }
+            _ => panic!("unexpected line '{}'", line),
+        }
+    }
+    mesh
+}
+
+#[test]
+fn obj_round_trip() {
+    // OBJ has no tangents
+    let mesh = teapot::Mesh {
+        tangents: Vec::new(),
+        ..teapot::create_vertices(7, 9)
+    };
+    let mut obj = Vec::new();
+    teapot::write_obj(&mesh, &mut obj).unwrap();
+    let read = read_obj(std::str::from_utf8(&obj).unwrap());
+
+    assert_eq!(read.num_vertices(), mesh.num_vertices());
+    assert_eq!(read.patch_ranges.len(), teapot::NUM_PATCHES);
+    assert_eq!(read, mesh);
+}
+
+#[test]
+fn obj_without_patches() {
+    let mesh = teapot::create_vertices(5, 5).weld(&teapot::WeldOptions::default());
+    let mesh = teapot::Mesh {
+        patch_ranges: Vec::new(),
+        tangents: Vec::new(),
+        ..mesh
+    };
+    let mut obj = Vec::new();
+    teapot::write_obj(&mesh, &mut obj).unwrap();
+    let read = read_obj(std::str::from_utf8(&obj).unwrap());
+    assert_eq!(read, mesh);
+}
+
+#[test]
+fn parse_obj_reads_written_mesh() {
+    let mesh = teapot::create_vertices(5, 6);
+    let mut obj = Vec::new();
+    teapot::write_obj(&mesh, &mut obj).unwrap();
+    let read = teapot::parse_obj(std::str::from_utf8(&obj).unwrap()).unwrap();
+
+    // vertices are numbered in the order the faces use them, which needn't
+    // be the order they were written in
+    assert_eq!(read.num_vertices(), mesh.num_vertices());
+    assert_eq!(read.indices.len(), mesh.indices.len());
+    for (&i, &j) in read.indices.iter().zip(mesh.indices.iter()) {
+        let (i, j) = (i as usize, j as usize);
+        assert_eq!(read.vertices[i], mesh.vertices[j]);
+        assert_eq!(read.normals[i], mesh.normals[j]);
+        assert_eq!(read.uvs[i], mesh.uvs[j]);
+    }
+}
+
+#[test]
+fn parse_obj_computes_missing_normals() {
+    // a unit cube, with quads wound outwards and negative indices on top
+    let text = "\
+        v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n\
+        v 0 0 1\nv 1 0 1\nv 1 1 1\nv 0 1 1\n\
+        vt 0 0\nvt 1 0\nvt 1 1\nvt 0 1\n\
+        f 1 4 3 2\n\
+        f -4/-4 -3/-3 -2/-2 -1/-1\n\
+        f 1 2 6 5\nf 2 3 7 6\nf 3 4 8 7\nf 4 1 5 8\n";
+    let mesh = teapot::parse_obj(text).unwrap();
+    assert_eq!(mesh.num_triangles(), 12);
+
+    // the top has its own vertices, because of its texture coordinates
+    assert_eq!(mesh.num_vertices(), 12);
+    for (v, n) in mesh.vertices.iter().zip(mesh.normals.iter()) {
+        // each corner's normal points out along the diagonal
+        for k in 0..3 {
+            let expected = (v[k] - 0.5) * 2.0 / 3f32.sqrt();
+            assert!((n[k] - expected).abs() < 1e-6, "{:?} {:?}", v, n);
+        }
+    }
+}
+
+#[test]
+fn parse_obj_errors() {
+    let err = teapot::parse_obj("v 0 0 0\nv 1 0 0\nf 1 2 3\n").unwrap_err();
+    assert_eq!(err.to_string(), "line 3: vertex index 3 out of range");
+    let err = teapot::parse_obj("v 0 0\n").unwrap_err();
+    assert_eq!(err.to_string(), "line 1: expected 3 to 7 values, found 2");
+    let err = teapot::parse_obj("v 0 0 0 1 0 0 1 0\n").unwrap_err();
+    assert_eq!(err.to_string(), "line 1: expected 3 to 7 values, found 8");
+}
+
+// as MeshLab writes them, with a colour after each position
+#[test]
+fn parse_obj_ignores_vertex_colors() {
+    let text = "\
+        v 0 0 0 1 0 0\n\
+        v 1 0 0 0 1 0\n\
+        v 0 1 0 0 0 1\n\
+        v 0 0 2 0.5\n\
+        f 1 2 3\n\
+        f 1 2 4\n";
+    let mesh = teapot::parse_obj(text).unwrap();
+    assert_eq!(mesh.num_triangles(), 2);
+    assert_eq!(mesh.vertices[0], [0.0, 0.0, 0.0, 1.0]);
+    assert_eq!(mesh.vertices[1], [1.0, 0.0, 0.0, 1.0]);
+    assert_eq!(mesh.vertices[2], [0.0, 1.0, 0.0, 1.0]);
+    // with four values the last is w
+    assert_eq!(mesh.vertices[3], [0.0, 0.0, 2.0, 0.5]);
+}
@@ -0,0 +1,100 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use cgmath::*;
+use teapot::{BezierPatch, BoundingBox, PatchSet};
+
+// A flat patch over [x, x + 3] X [0, 3] at height z
+fn flat_patch(x: f32, z: f32) -> BezierPatch {
+    let mut points = [[Point3::new(0.0, 0.0, 0.0); 4]; 4];
+    for (i, row) in points.iter_mut().enumerate() {
+        for (j, p) in row.iter_mut().enumerate() {
+            *p = Point3::new(x + j as f32, i as f32, z);
+        }
+    }
+    BezierPatch::new(points)
+}
+
+#[test]
+fn bounding_box_of_points() {
+    assert_eq!(BoundingBox::from_points(&[]), None);
+
+    let points = [
+        Point3::new(1.0, -2.0, 0.5),
+        Point3::new(-1.0, 4.0, 0.5),
+        Point3::new(0.0, 0.0, 2.5),
+    ];
+    let bbox = BoundingBox::from_points(&points).unwrap();
+    assert_eq!(bbox.min, Point3::new(-1.0, -2.0, 0.5));
+    assert_eq!(bbox.max, Point3::new(1.0, 4.0, 2.5));
+    assert_eq!(bbox.center(), Point3::new(0.0, 1.0, 1.5));
+    assert_eq!(bbox.size(), Vector3::new(2.0, 6.0, 2.0));
+
+    // a single point has no size
+    let bbox = BoundingBox::from_points(&points[..1]).unwrap();
+    assert_eq!(bbox.center(), points[0]);
+    assert_eq!(bbox.size(), Vector3::zero());
+
+    let mut grown = bbox;
+    grown.add_point(Point3::new(3.0, -2.0, 0.0));
+    assert_eq!(grown.min, Point3::new(1.0, -2.0, 0.0));
+    assert_eq!(grown.max, Point3::new(3.0, -2.0, 0.5));
+
+    let other = BoundingBox::from_points(&[Point3::new(-5.0, 0.0, 1.0)]).unwrap();
+    let union = grown.union(&other);
+    assert_eq!(union, other.union(&grown));
+    assert_eq!(union.min, Point3::new(-5.0, -2.0, 0.0));
+    assert_eq!(union.max, Point3::new(3.0, 0.0, 1.0));
+}
+
+#[test]
+fn patch_set_bounding_box() {
+    assert_eq!(PatchSet::new(Vec::new()).bounding_box(), None);
+
+    let patches: PatchSet = vec![flat_patch(0.0, 1.0), flat_patch(5.0, -1.0)]
+        .into_iter()
+        .collect();
+    assert_eq!(
+        patches[0].bounding_box().size(),
+        Vector3::new(3.0, 3.0, 0.0)
+    );
+    let bbox = patches.bounding_box().unwrap();
+    assert_eq!(bbox.min, Point3::new(0.0, 0.0, -1.0));
+    assert_eq!(bbox.max, Point3::new(8.0, 3.0, 1.0));
+
+    // the teapot sits on the xy plane, upright along z
+    let bbox = teapot::control_points().bounding_box().unwrap();
+    assert_eq!(bbox.min.z, 0.0);
+    assert!(bbox.size().z > 2.0 && bbox.size().z < bbox.size().x);
+}
+
+#[test]
+fn transforms_move_control_points() {
+    let patch = flat_patch(0.0, 1.0);
+    let t = Matrix4::from_translation(Vector3::new(1.0, 2.0, 3.0)) * Matrix4::from_scale(2.0);
+    let moved = patch.transform(&t);
+    for (p, q) in patch.iter().zip(moved.iter()) {
+        assert_eq!(*q, t.transform_point(*p));
+    }
+    assert_eq!(moved[(3, 3)], Point3::new(7.0, 8.0, 5.0));
+    let bbox = moved.bounding_box();
+    assert_eq!(bbox.min, Point3::new(1.0, 2.0, 5.0));
+    assert_eq!(bbox.size(), Vector3::new(6.0, 6.0, 0.0));
+
+    // whole sets move patch by patch
+    let teapot = teapot::control_points();
+    let rotation = Matrix4::from_angle_z(Deg(90.0));
+    let turned = teapot.transform(&rotation);
+    assert_eq!(turned.len(), teapot.len());
+    for (a, b) in teapot.iter().zip(turned.iter()) {
+        assert_eq!(*b, a.transform(&rotation));
+    }
+    let (before, after) = (
+        teapot.bounding_box().unwrap(),
+        turned.bounding_box().unwrap(),
+    );
+    assert!((after.size().x - before.size().y).abs() < 1e-4);
+    assert!((after.size().y - before.size().x).abs() < 1e-4);
+
+    // the identity leaves them alone
+    assert_eq!(teapot.transform(&Matrix4::identity()), teapot);
+}
@@ -0,0 +1,39 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+#[test]
+fn ply_counts_and_properties() {
+    let mesh = teapot::create_vertices(6, 7);
+    for &(normals, uvs) in [(false, false), (true, false), (false, true), (true, true)].iter() {
+        let options = teapot::PlyOptions { normals, uvs };
+        let mut ply = Vec::new();
+        teapot::write_ply(&mesh, &options, &mut ply).unwrap();
+        let text = String::from_utf8(ply).unwrap();
+
+        let mut lines = text.lines();
+        let header: Vec<&str> = lines.by_ref().take_while(|l| *l != "end_header").collect();
+        assert_eq!(header[0], "ply");
+        assert!(header.contains(&"format ascii 1.0"));
+        let vertex_element = format!("element vertex {}", mesh.num_vertices());
+        assert!(header.contains(&vertex_element.as_str()));
+        let properties = header
+            .iter()
+            .filter(|l| l.starts_with("property float"))
+            .count();
+        let fields = 3 + 3 * normals as usize + 2 * uvs as usize;
+        assert_eq!(properties, fields);
+
+        let body: Vec<&str> = lines.collect();
+        assert_eq!(body.len(), mesh.num_vertices() + mesh.num_triangles());
+        for (i, line) in body[..mesh.num_vertices()].iter().enumerate() {
+            let values: Vec<f32> = line.split(' ').map(|f| f.parse().unwrap()).collect();
+            assert_eq!(values.len(), fields);
+            assert_eq!(values[..3], mesh.vertices[i][..3]);
+        }
+        for (line, tri) in body[mesh.num_vertices()..]
+            .iter()
+            .zip(mesh.indices.chunks(3))
+        {
+            assert_eq!(*line, format!("3 {} {} {}", tri[0], tri[1], tri[2]));
+        }
+    }
+}
@@ -0,0 +1,56 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use cgmath::*;
+use teapot::Ray;
+
+#[test]
+fn rays_along_normals_hit_where_they_started() {
+    let patches = teapot::control_points();
+
+    // the body, lid and bottom, where nothing else is in the way
+    let unobstructed = (0..12).chain(24..32);
+    for i in unobstructed {
+        for &(u, v) in &[(0.5, 0.5), (0.25, 0.75), (0.8, 0.4)] {
+            let p = patches[i].evaluate(u, v);
+            let ray = Ray::new(p.position + 3.0 * p.normal, -p.normal);
+            let hit = patches.intersect_ray(&ray).unwrap();
+            assert_eq!(hit.patch, i);
+            assert!((hit.distance - 3.0).abs() < 1e-6, "{:?}", hit);
+            assert!((hit.point.u - u).abs() < 1e-6 && (hit.point.v - v).abs() < 1e-6);
+            assert!((hit.point.normal - p.normal).magnitude() < 1e-6);
+        }
+    }
+}
+
+#[test]
+fn ray_down_onto_knob() {
+    let patches = teapot::control_points();
+    let ray = Ray::new(Point3::new(0.0, 0.0, 10.0), Vector3::new(0.0, 0.0, -2.0));
+    let hit = patches.intersect_ray(&ray).unwrap();
+    assert!((20..24).contains(&hit.patch));
+    assert!((hit.distance - (10.0 - 3.15)).abs() < 1e-6);
+    assert!(hit.point.v.abs() < 1e-6);
+    assert!((hit.point.normal - Vector3::unit_z()).magnitude() < 1e-6);
+}
+
+#[test]
+fn ray_through_side_hits_body() {
+    // the widest part of the body is a circle of radius 2 at z = 0.9, to
+    // within the accuracy of the teapot's approximation of a circle
+    let patches = teapot::control_points();
+    let (sin, cos) = Deg(-30.0f64).sin_cos();
+    let origin = Point3::new(10.0 * cos, 10.0 * sin, 0.9);
+    let ray = Ray::new(origin, Point3::new(0.0, 0.0, 0.9) - origin);
+    let hit = patches.intersect_ray(&ray).unwrap();
+    assert!([4, 8].contains(&hit.patch));
+    assert!((hit.distance - 8.0).abs() < 1e-2, "{:?}", hit);
+}
+
+#[test]
+fn rays_that_miss() {
+    let patches = teapot::control_points();
+    let up = Ray::new(Point3::new(0.0, 0.0, 10.0), Vector3::unit_z());
+    assert!(patches.intersect_ray(&up).is_none());
+    let past = Ray::new(Point3::new(0.0, -10.0, 5.0), Vector3::unit_y());
+    assert!(patches.intersect_ray(&past).is_none());
+}
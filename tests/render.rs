@@ -0,0 +1,227 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use cgmath::{Matrix4, SquareMatrix};
+use miniz_oxide::inflate::decompress_to_vec_zlib;
+use teapot::{Image, RenderTarget, Vertex};
+
+// A triangle covering the middle of the screen at depth `z` with an
+// identity transform
+fn triangle(z: f32, normal: [f32; 3]) -> Vec<Vertex> {
+    [[-0.8, -0.8], [0.8, -0.8], [0.0, 0.8]]
+        .iter()
+        .map(|&[x, y]| Vertex {
+            pos: [x, y, z, 1.0],
+            normal,
+            ..Vertex::default()
+        })
+        .collect()
+}
+
+#[test]
+fn nearest_triangle_wins_in_either_order() {
+    let near = triangle(0.25, [0.0, 1.0, 0.0]);
+    let far = triangle(0.5, [0.0, 0.0, 1.0]);
+    let identity = Matrix4::identity();
+
+    let mut images = Vec::new();
+    for order in [[&near, &far], [&far, &near]].iter() {
+        let mut target = RenderTarget::new(32, 32);
+        for vertices in order.iter() {
+            target.draw_indexed(vertices, &[0, 1, 2], &identity);
+        }
+        assert!((target.depth(16, 16) - 0.25).abs() < 1e-6);
+        assert_eq!(target.depth(0, 0), 1.0);
+        images.push(target.to_image());
+    }
+    assert_eq!(images[0], images[1]);
+
+    let mut target = RenderTarget::new(32, 32);
+    target.draw_indexed(&near, &[0, 1, 2], &identity);
+    assert_eq!(images[0].pixel(16, 16), target.to_image().pixel(16, 16));
+}
+
+#[test]
+fn triangles_are_clipped_to_the_near_plane() {
+    // half in front of the near plane, half behind
+    let mut vertices = triangle(0.5, [0.0, 0.0, 1.0]);
+    vertices[2].pos[2] = -0.5;
+    let mut target = RenderTarget::new(32, 32);
+    target.draw_indexed(&vertices, &[0, 1, 2], &Matrix4::identity());
+
+    // y points down the screen, so the bottom corners are at the top
+    let image = target.to_image();
+    assert_ne!(image.pixel(16, 6), [0, 0, 0]);
+    assert_eq!(image.pixel(16, 26), [0, 0, 0]);
+    assert!(target.depth(16, 6) >= 0.0);
+}
+
+#[test]
+fn png_layout() {
+    let mut image = Image::new(3, 2);
+    image.set_pixel(2, 1, [255, 128, 0]);
+    let mut png = Vec::new();
+    image.write_png(&mut png).unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    assert_eq!(&png[12..16], b"IHDR");
+    assert_eq!(&png[16..24], &[0, 0, 0, 3, 0, 0, 0, 2]);
+    assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+
+    // IDAT follows the 25 byte IHDR chunk and inflates to the filtered rows
+    let len = u32::from_be_bytes([png[33], png[34], png[35], png[36]]) as usize;
+    assert_eq!(&png[37..41], b"IDAT");
+    let rows = decompress_to_vec_zlib(&png[41..41 + len]).unwrap();
+    assert_eq!(rows.len(), 2 * (1 + 3 * 3));
+    assert!(rows[..17].iter().all(|&b| b == 0));
+    assert_eq!(&rows[17..], &[255, 128, 0]);
+}
+
+#[test]
+fn png_is_compressed() {
+    let mut image = Image::new(256, 256);
+    image.set_pixel(10, 20, [255, 255, 255]);
+    let mut png = Vec::new();
+    image.write_png(&mut png).unwrap();
+    assert!(png.len() < 2000, "{} bytes", png.len());
+}
+
+#[test]
+fn ppm_round_trip() {
+    let mut image = Image::new(4, 3);
+    image.set_pixel(1, 2, [10, 20, 30]);
+    let mut ppm = Vec::new();
+    image.write_ppm(&mut ppm).unwrap();
+    assert!(ppm.starts_with(b"P6\n4 3\n255\n"));
+    assert_eq!(teapot::parse_ppm(&ppm).unwrap(), image);
+
+    let err = teapot::parse_ppm(b"P3\n1 1\n255\n0 0 0").unwrap_err();
+    assert_eq!(err.to_string(), "not a binary PPM file");
+}
+
+#[test]
+fn save_checks_the_extension_first() {
+    let path = std::env::temp_dir().join(format!("teapot-{}.bmp", std::process::id()));
+    let err = Image::new(2, 2).save(&path).unwrap_err();
+    assert_eq!(err.to_string(), "don't know how to write '.bmp' files");
+    assert!(!path.exists());
+}
+
+// Set UPDATE_GOLDEN=1 to write a new golden image after an intended change
+// to the shading
+#[test]
+fn teapot_matches_golden_image() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/teapot.ppm");
+    let mesh = teapot::create_vertices(18, 23);
+    let transform = teapot::generate_matrix(96.0 / 72.0, 0.5);
+    let image = teapot::render(&mesh.interleave(), &mesh.indices, &transform, 96, 72);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        image.save(path).unwrap();
+    }
+
+    // allow for rounding along the edges of triangles
+    let golden = teapot::load_ppm(path).unwrap();
+    let differing = image.differing_pixels(&golden, 2);
+    assert!(differing <= 20, "{} pixels differ from {}", differing, path);
+}
+
+#[test]
+fn turntable_covers_one_orbit() {
+    let mesh = teapot::create_vertices(8, 8);
+    let vertices = mesh.interleave();
+    let frames: Vec<Image> =
+        teapot::turntable(&vertices, &mesh.indices, &Default::default(), 48, 36, 4).collect();
+    assert_eq!(frames.len(), 4);
+
+    // a quarter of the orbit apart, and a whole orbit back to the start
+    let at = |time: f32| {
+        let transform = teapot::generate_matrix(48.0 / 36.0, time);
+        teapot::render(&vertices, &mesh.indices, &transform, 48, 36)
+    };
+    let quarter = teapot::ORBIT_PERIOD / 4.0;
+    assert_eq!(frames[1].differing_pixels(&at(quarter), 0), 0);
+    assert!(frames[0].differing_pixels(&at(teapot::ORBIT_PERIOD), 2) <= 5);
+    assert!(frames[0].differing_pixels(&frames[1], 2) > 100);
+}
+
+// A highlight in the middle of a triangle, between vertices whose normals
+// all point away from it, is lost by lighting the vertices
+#[test]
+fn phong_shading_finds_highlights_between_vertices() {
+    use cgmath::{InnerSpace, Vector3};
+
+    // with an identity transform the eye looks along +z, so the highlight
+    // is where the normal is the half vector
+    let lighting = teapot::Lighting::default();
+    let light = Vector3::from(lighting.light.direction).normalize();
+    let half = (light - Vector3::unit_z()).normalize();
+    let u = half.cross(Vector3::unit_x()).normalize();
+    let v = half.cross(u);
+    let mut vertices = triangle(0.5, [0.0; 3]);
+    for (vertex, angle) in vertices.iter_mut().zip([0.0f32, 2.1, 4.2].iter()) {
+        let tilt = u * angle.cos() + v * angle.sin();
+        vertex.normal = (half + tilt * 0.6).normalize().into();
+    }
+
+    let mut images = Vec::new();
+    for shading in [teapot::Shading::Gouraud, teapot::Shading::Phong].iter() {
+        let mut target = RenderTarget::new(32, 32);
+        target.set_lighting(teapot::Lighting {
+            shading: *shading,
+            ..lighting
+        });
+        target.draw_indexed(&vertices, &[0, 1, 2], &Matrix4::identity());
+        images.push(target.to_image());
+    }
+
+    // the centroid, (0, -0.27) in clip space
+    let (gouraud, phong) = (images[0].pixel(16, 11), images[1].pixel(16, 11));
+    assert!(phong[1] > gouraud[1] + 50, "{:?} {:?}", gouraud, phong);
+}
+
+// Gouraud shading keeps the original shader's single view direction, while
+// Phong shading looks from the camera to each pixel, so through the
+// viewer's perspective camera a flat triangle is lit evenly by the one and
+// unevenly by the other
+#[test]
+fn phong_shading_looks_from_the_camera() {
+    use cgmath::{InnerSpace, Matrix3, Vector3};
+
+    let transform = teapot::generate_matrix(1.0, 0.0);
+    let m = Matrix3::from_cols(
+        transform.x.truncate(),
+        transform.y.truncate(),
+        transform.z.truncate(),
+    );
+    let view = (m.invert().unwrap() * Vector3::unit_z()).normalize();
+    let lighting = teapot::Lighting::default();
+    let light = Vector3::from(lighting.light.direction).normalize();
+    // facing the half vector along the view axis
+    let normal = (light - view).normalize();
+    let mut vertices = triangle(0.0, normal.into());
+    for vertex in vertices.iter_mut() {
+        vertex.pos[0] *= 3.0;
+        vertex.pos[1] *= 3.0;
+    }
+
+    let mut colors = Vec::new();
+    for shading in [teapot::Shading::Gouraud, teapot::Shading::Phong].iter() {
+        let mut target = RenderTarget::new(32, 32);
+        target.set_lighting(teapot::Lighting {
+            shading: *shading,
+            ..lighting
+        });
+        target.draw_indexed(&vertices, &[0, 1, 2], &transform);
+        let image = target.to_image();
+        let mut lit: Vec<[u8; 3]> = image
+            .pixels()
+            .iter()
+            .cloned()
+            .filter(|&p| p != [0, 0, 0])
+            .collect();
+        assert!(lit.len() > 100, "{} pixels lit", lit.len());
+        lit.sort_unstable();
+        lit.dedup();
+        colors.push(lit.len());
+    }
+    assert_eq!(colors[0], 1);
+    assert!(colors[1] > 10, "{} colours", colors[1]);
+}
@@ -0,0 +1,153 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use std::collections::HashMap;
+use teapot::{PatchSet, Side};
+
+// Number of edges used by only one triangle, matching edges by the exact
+// positions of their ends. Edges collapsed to a point are ignored.
+fn unmatched_edges(mesh: &teapot::Mesh) -> usize {
+    let key = |i: u32| {
+        let v = mesh.vertices[i as usize];
+        [v[0].to_bits(), v[1].to_bits(), v[2].to_bits()]
+    };
+    let mut counts = HashMap::new();
+    for tri in mesh.indices.chunks(3) {
+        for k in 0..3 {
+            let a = key(tri[k]);
+            let b = key(tri[(k + 1) % 3]);
+            if a != b {
+                *counts.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+            }
+        }
+    }
+    counts.values().filter(|&&n| n == 1).count()
+}
+
+// Number of edges there should be along the sides of the patches that
+// don't have a neighbour, given each patch's rows and columns of vertices.
+fn expected_open_edges(patches: &PatchSet, resolutions: &[(usize, usize)]) -> usize {
+    let adjacency = teapot::patch_adjacency(patches, 1e-6);
+    let mut count = 0;
+    for (i, patch) in patches.iter().enumerate() {
+        for (k, &side) in Side::ALL.iter().enumerate() {
+            let edge = patch.edge(side);
+            if adjacency[i][k].is_some() || edge.iter().all(|p| *p == edge[0]) {
+                continue;
+            }
+            let (nr, nc) = resolutions[i];
+            count += match side {
+                Side::Bottom | Side::Top => nc - 1,
+                Side::Left | Side::Right => nr - 1,
+            };
+        }
+    }
+    count
+}
+
+#[test]
+fn teapot_adjacency() {
+    let patches = teapot::control_points();
+    let adjacency = teapot::patch_adjacency(&patches, 1e-6);
+
+    // adjacency is symmetric
+    for (i, sides) in adjacency.iter().enumerate() {
+        for (k, n) in sides.iter().enumerate() {
+            if let Some(n) = n {
+                let back = adjacency[n.patch][n.side as usize].unwrap();
+                assert_eq!((back.patch, back.side), (i, Side::ALL[k]));
+                assert_eq!(back.reversed, n.reversed);
+            }
+        }
+    }
+
+    // the bottom of the body meets the bottom of the teapot
+    let n = adjacency[8][Side::Top as usize].unwrap();
+    assert_eq!(n.patch, 31);
+    assert_eq!(n.side, Side::Top);
+    assert!(n.reversed);
+}
+
+#[test]
+fn uniform_tessellation_has_no_cracks() {
+    let patches = teapot::control_points();
+    for &(nr, nc) in &[(18, 23), (7, 4), (3, 9)] {
+        let mesh = teapot::create_vertices(nr, nc);
+        let resolutions = vec![(nr, nc); patches.len()];
+        assert_eq!(
+            unmatched_edges(&mesh),
+            expected_open_edges(&patches, &resolutions),
+            "{} X {}",
+            nr,
+            nc
+        );
+    }
+}
+
+#[test]
+fn adaptive_tessellation_has_no_cracks() {
+    let patches = teapot::control_points();
+    for &tolerance in &[0.1, 0.01, 0.002] {
+        let mesh = teapot::tesselate_adaptive(&patches, tolerance);
+        let resolutions: Vec<_> = patches
+            .iter()
+            .map(|p| teapot::patch_resolution(p, tolerance))
+            .collect();
+        assert_eq!(
+            unmatched_edges(&mesh),
+            expected_open_edges(&patches, &resolutions),
+            "tolerance {}",
+            tolerance
+        );
+    }
+}
+
+#[test]
+fn mixed_resolutions_have_no_cracks() {
+    let patches = teapot::control_points();
+    let resolutions: Vec<_> = (0..patches.len())
+        .map(|i| (2 + i % 5, 2 + (i * 7) % 6))
+        .collect();
+    let mesh = teapot::tesselate_stitched(&patches, &resolutions);
+    assert_eq!(
+        unmatched_edges(&mesh),
+        expected_open_edges(&patches, &resolutions)
+    );
+}
+
+// Every attribute of every vertex as raw bits, so that 0.0 and -0.0 differ
+fn vertex_bits(mesh: &teapot::Mesh) -> Vec<u32> {
+    mesh.interleave()
+        .iter()
+        .flat_map(|v| {
+            let floats = v.pos.iter().chain(&v.normal).chain(&v.uv).chain(&v.tangent);
+            floats.map(|f| f.to_bits()).collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[test]
+fn parallel_matches_serial() {
+    // only runs concurrently with the parallel feature, but the two must
+    // agree either way
+    let patches = teapot::control_points();
+    let uniform = vec![(9, 13); patches.len()];
+    let mixed: Vec<_> = (0..patches.len())
+        .map(|i| (2 + i % 5, 2 + (i * 7) % 6))
+        .collect();
+    for resolutions in &[uniform, mixed] {
+        let serial = teapot::tesselate_stitched_serial(&patches, resolutions);
+        let mesh = teapot::tesselate_stitched(&patches, resolutions);
+        assert_eq!(mesh.indices, serial.indices);
+        assert_eq!(mesh.patch_ranges, serial.patch_ranges);
+        assert_eq!(vertex_bits(&mesh), vertex_bits(&serial));
+    }
+}
+
+#[test]
+#[should_panic(expected = "a patch needs at least 2 rows and columns of vertices, got 1 X 4")]
+fn resolution_below_two_panics() {
+    let patches = teapot::control_points();
+    let mut resolutions = vec![(4, 4); patches.len()];
+    resolutions[5] = (1, 4);
+    teapot::tesselate_stitched(&patches, &resolutions);
+}
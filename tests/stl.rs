@@ -0,0 +1,58 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+fn read_f32(bytes: &[u8], offset: usize) -> f32 {
+    let mut b = [0u8; 4];
+    b.copy_from_slice(&bytes[offset..offset + 4]);
+    f32::from_le_bytes(b)
+}
+
+#[test]
+fn stl_facet_normals() {
+    let mesh = teapot::create_vertices(10, 10);
+    let mut stl = Vec::new();
+    teapot::write_stl(&mesh, &mut stl).unwrap();
+
+    let mut count = [0u8; 4];
+    count.copy_from_slice(&stl[80..84]);
+    assert_eq!(u32::from_le_bytes(count) as usize, mesh.num_triangles());
+    assert_eq!(stl.len(), 84 + 50 * mesh.num_triangles());
+
+    for (t, tri) in mesh.indices.chunks(3).enumerate() {
+        let facet = 84 + 50 * t;
+        let n: Vec<f32> = (0..3).map(|k| read_f32(&stl, facet + 4 * k)).collect();
+        // the collapsed sides of the knob and the bottom are fans to a
+        // single vertex, so no triangle is degenerate
+        let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+        assert!((len - 1.0).abs() < 1e-5, "triangle {} is degenerate", t);
+
+        // the facet normal follows the winding, which agrees with the
+        // surface normals at its corners
+        for &i in tri {
+            let vn = mesh.normals[i as usize];
+            let dot = n[0] * vn[0] + n[1] * vn[1] + n[2] * vn[2];
+            assert!(dot > 0.0, "triangle {} faces away from its normals", t);
+        }
+        for (k, &i) in tri.iter().enumerate() {
+            let v = mesh.vertices[i as usize];
+            for (axis, &x) in v[..3].iter().enumerate() {
+                assert_eq!(read_f32(&stl, facet + 12 + 12 * k + 4 * axis), x);
+            }
+        }
+    }
+}
+
+#[test]
+fn stl_ascii_matches_binary() {
+    let mesh = teapot::create_vertices(5, 6);
+    let mut text = Vec::new();
+    teapot::write_stl_ascii(&mesh, &mut text).unwrap();
+    let text = String::from_utf8(text).unwrap();
+
+    let lines: Vec<&str> = text.lines().map(|l| l.trim()).collect();
+    assert_eq!(lines.first(), Some(&"solid teapot"));
+    assert_eq!(lines.last(), Some(&"endsolid teapot"));
+    let facets = lines.iter().filter(|l| l.starts_with("facet normal")).count();
+    let vertices = lines.iter().filter(|l| l.starts_with("vertex")).count();
+    assert_eq!(facets, mesh.num_triangles());
+    assert_eq!(vertices, 3 * mesh.num_triangles());
+}
@@ -0,0 +1,59 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use cgmath::*;
+use teapot::BezierPatch;
+
+// The paraboloid z = x² + y² over the unit square, exactly
+fn paraboloid() -> BezierPatch {
+    let a = [0.0, 0.0, 1.0 / 3.0, 1.0];
+    let mut points = [[Point3::new(0.0, 0.0, 0.0); 4]; 4];
+    for (r, row) in points.iter_mut().enumerate() {
+        for (c, p) in row.iter_mut().enumerate() {
+            *p = Point3::new(c as f32 / 3.0, r as f32 / 3.0, a[c] + a[r]);
+        }
+    }
+    BezierPatch::new(points)
+}
+
+#[test]
+fn paraboloid_curvature() {
+    let p = paraboloid().evaluate(0.0, 0.0);
+    assert!(p.position.to_vec().magnitude() < 1e-6);
+    assert!((p.normal - Vector3::unit_z()).magnitude() < 1e-6);
+
+    let k = p.curvature().unwrap();
+    assert!((k.gaussian - 4.0).abs() < 1e-5, "{:?}", k);
+    assert!((k.mean - 2.0).abs() < 1e-5, "{:?}", k);
+    assert!((k.principal[0] - 2.0).abs() < 1e-3 && (k.principal[1] - 2.0).abs() < 1e-3);
+
+    // away from the axis the curvature along the radius is larger
+    let p = paraboloid().evaluate(1.0, 0.0);
+    let k = p.curvature().unwrap();
+    let expected_gaussian = 4.0 / (1.0f64 + 4.0).powi(2);
+    assert!((k.gaussian - expected_gaussian).abs() < 1e-5, "{:?}", k);
+    assert!(k.directions[0].dot(k.directions[1]).abs() < 1e-6);
+}
+
+#[test]
+fn derivatives_match_finite_differences() {
+    let patches = teapot::control_points();
+    let h = 1e-5;
+    for patch in &patches {
+        for &(u, v) in &[(0.3, 0.6), (0.5, 0.5), (0.9, 0.1)] {
+            let p = patch.evaluate(u, v);
+            let pu =
+                (patch.evaluate(u + h, v).position - patch.evaluate(u - h, v).position) / (2.0 * h);
+            let pv =
+                (patch.evaluate(u, v + h).position - patch.evaluate(u, v - h).position) / (2.0 * h);
+            assert!((p.du - pu).magnitude() < 1e-5);
+            assert!((p.dv - pv).magnitude() < 1e-5);
+
+            let puu = (patch.evaluate(u + h, v).du - patch.evaluate(u - h, v).du) / (2.0 * h);
+            let puv = (patch.evaluate(u, v + h).du - patch.evaluate(u, v - h).du) / (2.0 * h);
+            let pvv = (patch.evaluate(u, v + h).dv - patch.evaluate(u, v - h).dv) / (2.0 * h);
+            assert!((p.duu - puu).magnitude() < 1e-4);
+            assert!((p.duv - puv).magnitude() < 1e-4);
+            assert!((p.dvv - pvv).magnitude() < 1e-4);
+        }
+    }
+}
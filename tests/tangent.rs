@@ -0,0 +1,60 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use cgmath::InnerSpace;
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+#[test]
+fn patch_tangents_follow_u() {
+    let mesh = teapot::create_vertices(12, 12);
+    assert!(mesh.has_tangents());
+    let patches = teapot::control_points();
+    for (i, range) in mesh.patch_ranges.iter().enumerate() {
+        for &v in mesh.indices[range.clone()].iter() {
+            let v = v as usize;
+            let t = mesh.tangents[v];
+            let n = mesh.normals[v];
+            assert!((dot(&t[..3], &t[..3]) - 1.0).abs() < 1e-5);
+            assert!(dot(&t[..3], &n).abs() < 1e-5);
+            assert!(t[3] == 1.0 || t[3] == -1.0);
+
+            // away from collapsed rows the tangent is along du, and the
+            // frame is right handed because the normal is du × dv
+            let [u, w] = mesh.uvs[v];
+            let p = patches[i].evaluate(u as f64, w as f64);
+            if p.du.magnitude() > 1e-3 {
+                let du = p.du.normalize();
+                let du = [du.x as f32, du.y as f32, du.z as f32];
+                assert!(dot(&t[..3], &du) > 0.999, "patch {} at ({}, {})", i, u, w);
+                assert_eq!(t[3], 1.0);
+            }
+        }
+    }
+}
+
+#[test]
+fn welded_tangents_stay_perpendicular() {
+    let mesh = teapot::create_vertices(8, 8).weld(&teapot::WeldOptions::default());
+    assert!(mesh.has_tangents());
+    for (t, n) in mesh.tangents.iter().zip(mesh.normals.iter()) {
+        assert!((dot(&t[..3], &t[..3]) - 1.0).abs() < 1e-5);
+        assert!(dot(&t[..3], n).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn generated_tangents_follow_texture() {
+    // a square in the xy plane with a mirrored texture, u along -y and v
+    // along -x, which is a left handed frame about +z
+    let text = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n\
+                vt 1 1\nvt 1 0\nvt 0 0\nvt 0 1\n\
+                f 1/1 2/2 3/3 4/4\n";
+    let mesh = teapot::parse_obj(text).unwrap();
+    for t in mesh.tangents.iter() {
+        assert!((t[0] - 0.0).abs() < 1e-6);
+        assert!((t[1] + 1.0).abs() < 1e-6);
+        assert_eq!(t[3], -1.0);
+    }
+}
@@ -0,0 +1,122 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use teapot::{Mesh, ValidateOptions};
+
+// A closed tetrahedron wound outwards, with normals pointing away from its
+// centre
+fn tetrahedron() -> Mesh {
+    let corners = [
+        [1.0, 1.0, 1.0],
+        [1.0, -1.0, -1.0],
+        [-1.0, 1.0, -1.0],
+        [-1.0, -1.0, 1.0],
+    ];
+    let mut mesh = Mesh::new();
+    for c in corners.iter() {
+        let s = 1.0 / 3f32.sqrt();
+        mesh.vertices.push([c[0], c[1], c[2], 1.0]);
+        mesh.normals.push([c[0] * s, c[1] * s, c[2] * s]);
+        mesh.uvs.push([0.0, 0.0]);
+    }
+    mesh.indices = vec![0, 1, 2, 0, 3, 1, 0, 2, 3, 1, 3, 2];
+    mesh
+}
+
+#[test]
+fn tetrahedron_is_valid_and_closed() {
+    let report = tetrahedron().validate(&ValidateOptions::default());
+    assert!(report.is_valid(), "{}", report);
+    assert!(report.is_closed(), "{}", report);
+}
+
+#[test]
+fn reports_each_problem() {
+    let options = ValidateOptions::default();
+
+    let mut mesh = tetrahedron();
+    mesh.indices.swap(0, 1);
+    let report = mesh.validate(&options);
+    assert_eq!(report.flipped_triangles, vec![0]);
+
+    let mut mesh = tetrahedron();
+    mesh.normals[3][1] = f32::NAN;
+    assert_eq!(mesh.validate(&options).non_finite_vertices, vec![3]);
+
+    let mut mesh = tetrahedron();
+    mesh.indices.extend_from_slice(&[0, 1, 4]);
+    assert_eq!(mesh.validate(&options).invalid_triangles, vec![4]);
+
+    // a fin on edge 0-1, which also leaves its other two edges open
+    let mut mesh = tetrahedron();
+    mesh.vertices.push([2.0, 2.0, -2.0, 1.0]);
+    mesh.normals.push([1.0, 0.0, 0.0]);
+    mesh.uvs.push([0.0, 0.0]);
+    mesh.indices.extend_from_slice(&[0, 1, 4]);
+    let report = mesh.validate(&options);
+    assert_eq!(report.non_manifold_edges, vec![[0, 1]]);
+    assert_eq!(report.boundary_loops, vec![vec![1, 4, 0]]);
+
+    // a sliver across one face, thinner than the tolerance
+    let mut mesh = tetrahedron();
+    mesh.vertices.push([0.0, 1e-6, -1.0, 1.0]);
+    mesh.normals.push([0.0, 0.0, -1.0]);
+    mesh.uvs.push([0.0, 0.0]);
+    mesh.indices.extend_from_slice(&[2, 4, 1]);
+    assert_eq!(mesh.validate(&options).degenerate_triangles, vec![4]);
+}
+
+#[test]
+fn teapot_report() {
+    let nc = 12;
+    let mesh = teapot::create_vertices(9, nc);
+    let report = mesh.validate(&ValidateOptions::default());
+
+    // the collapsed first rows of the lid knob and the bottom are fans,
+    // without zero-area triangles
+    assert!(report.is_valid(), "{}", report);
+
+    // the open ends of the handle and spout, the rim of the lid and the
+    // top of the body
+    let mut lengths: Vec<_> = report.boundary_loops.iter().map(|b| b.len()).collect();
+    lengths.sort();
+    let rim = 4 * (nc - 1);
+    let end = 2 * (nc - 1);
+    assert_eq!(lengths, vec![end, end, end, end, rim, rim]);
+
+    // welding doesn't close anything
+    let welded = mesh.weld(&teapot::WeldOptions::default());
+    let report = welded.validate(&ValidateOptions::default());
+    assert!(report.is_valid(), "{}", report);
+    assert_eq!(report.boundary_loops.len(), 6);
+}
+
+#[test]
+fn stitched_teapot_has_no_degenerate_triangles() {
+    let patches = teapot::control_points();
+    for &tolerance in &[0.1, 0.01] {
+        let mesh = teapot::tesselate_adaptive(&patches, tolerance);
+        let report = mesh.validate(&ValidateOptions::default());
+        assert!(report.is_valid(), "tolerance {}: {}", tolerance, report);
+    }
+    let resolutions: Vec<_> = (0..patches.len())
+        .map(|i| (2 + i % 5, 2 + (i * 7) % 6))
+        .collect();
+    let mesh = teapot::tesselate_stitched(&patches, &resolutions);
+    let report = mesh.validate(&ValidateOptions::default());
+    // (some of these grids are too coarse to follow the spout's lip, which
+    // folds triangles over)
+    assert!(report.degenerate_triangles.is_empty(), "{}", report);
+    assert!(report.non_manifold_edges.is_empty(), "{}", report);
+}
+
+#[test]
+fn single_patch_fans() {
+    // the lid knob has one vertex at its top, and a triangle to it from
+    // each cell of the next row
+    let patch = &teapot::control_points()[20];
+    let (nr, nc) = (6, 9);
+    let mesh = teapot::tesselate_patch(patch, nr, nc);
+    assert_eq!(mesh.num_vertices(), 1 + (nr - 1) * nc);
+    assert_eq!(mesh.num_triangles(), (nc - 1) + 2 * (nr - 2) * (nc - 1));
+    assert!(mesh.validate(&ValidateOptions::default()).is_valid());
+}
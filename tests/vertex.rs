@@ -0,0 +1,33 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use teapot::Vertex;
+use zerocopy::AsBytes;
+
+fn floats(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+#[test]
+fn interleaved_layout() {
+    assert_eq!(Vertex::STRIDE, 52);
+
+    let mesh = teapot::create_vertices(3, 5);
+    let vertices = mesh.interleave();
+    assert_eq!(vertices.len(), mesh.num_vertices());
+
+    let bytes = vertices.as_bytes();
+    for i in 0..mesh.num_vertices() {
+        let vertex = &bytes[i * Vertex::STRIDE..(i + 1) * Vertex::STRIDE];
+        let pos = Vertex::POS_OFFSET;
+        let normal = Vertex::NORMAL_OFFSET;
+        let uv = Vertex::UV_OFFSET;
+        let tangent = Vertex::TANGENT_OFFSET;
+        assert_eq!(floats(&vertex[pos..pos + 16]), mesh.vertices[i]);
+        assert_eq!(floats(&vertex[normal..normal + 12]), mesh.normals[i]);
+        assert_eq!(floats(&vertex[uv..uv + 8]), mesh.uvs[i]);
+        assert_eq!(floats(&vertex[tangent..tangent + 16]), mesh.tangents[i]);
+    }
+}
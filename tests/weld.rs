@@ -0,0 +1,73 @@
+// Teapot demo © 2020 RustyTriangles LLC
+
+use std::collections::HashMap;
+
+fn edge_counts(mesh: &teapot::Mesh) -> HashMap<(u32, u32), usize> {
+    let mut counts = HashMap::new();
+    for tri in mesh.indices.chunks(3) {
+        for k in 0..3 {
+            let a = tri[k];
+            let b = tri[(k + 1) % 3];
+            *counts.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+#[test]
+fn weld_connects_patches() {
+    let mesh = teapot::create_vertices(10, 10);
+    let welded = mesh.weld(&teapot::WeldOptions::default());
+
+    // each patch shares at least one edge with its neighbours
+    assert!(welded.num_vertices() < mesh.num_vertices() - 32 * 9);
+    assert_eq!(welded.normals.len(), welded.num_vertices());
+    assert_eq!(welded.uvs.len(), welded.num_vertices());
+
+    // every edge is used by one or two triangles
+    let counts = edge_counts(&welded);
+    assert!(counts.values().all(|&n| n <= 2));
+    assert!(counts.values().filter(|&&n| n == 2).count() > counts.len() / 2);
+
+    // no triangles were collapsed to a line or point
+    for tri in welded.indices.chunks(3) {
+        assert!(tri[0] != tri[1] && tri[1] != tri[2] && tri[2] != tri[0]);
+    }
+}
+
+#[test]
+fn weld_respects_crease_angle() {
+    // two triangles meeting at a right angle
+    let mesh = teapot::Mesh {
+        vertices: vec![
+            [0.0, 0.0, 0.0, 1.0],
+            [1.0, 0.0, 0.0, 1.0],
+            [0.0, 1.0, 0.0, 1.0],
+            [0.0, 0.0, 0.0, 1.0],
+            [0.0, 1.0, 0.0, 1.0],
+            [0.0, 0.0, 1.0, 1.0],
+        ],
+        normals: vec![
+            [0.0, 0.0, 1.0],
+            [0.0, 0.0, 1.0],
+            [0.0, 0.0, 1.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+        ],
+        uvs: vec![[0.0, 0.0]; 6],
+        indices: vec![0, 1, 2, 3, 4, 5],
+        ..Default::default()
+    };
+
+    let smooth = mesh.weld(&teapot::WeldOptions::default());
+    assert_eq!(smooth.num_vertices(), 4);
+    let n = smooth.normals[0];
+    assert!((n[0] - 0.5f32.sqrt()).abs() < 1e-6 && (n[2] - 0.5f32.sqrt()).abs() < 1e-6);
+
+    let sharp = mesh.weld(&teapot::WeldOptions {
+        crease_angle: cgmath::Deg(45.0),
+        ..Default::default()
+    });
+    assert_eq!(sharp, mesh);
+}